@@ -0,0 +1,105 @@
+// Time to parse one fetched transaction into an explanation and its summaries. The response is
+// handed over directly and the client is warmed up first, so neither the network nor a first-use
+// lookup is measured. Run with `cargo bench`.
+
+use criterion::{Criterion, criterion_group, criterion_main};
+use serde_json::json;
+use std::sync::Arc;
+use sui_json_rpc_types::SuiTransactionBlockResponse;
+use sui_types::digests::TransactionDigest;
+
+use sui_readable::config::Config;
+use sui_readable::models::ExplainOptions;
+use sui_readable::sui_client::SuiClient;
+
+fn address(seed: u8) -> String {
+    format!("0x{}", format!("{:02x}", seed).repeat(32))
+}
+
+// A transfer of SUI to two recipients that also creates a few objects
+fn transfer(digest: TransactionDigest) -> SuiTransactionBlockResponse {
+    let sender = address(0xa1);
+    let balance = |owner: &str, amount: i128| {
+        json!({
+            "owner": {"AddressOwner": owner},
+            "coinType": "0x2::sui::SUI",
+            "amount": amount.to_string(),
+        })
+    };
+    let created = |seed: u8| {
+        json!({
+            "type": "created",
+            "sender": sender,
+            "owner": {"AddressOwner": address(0xb2)},
+            "objectType": "0x2::coin::Coin<0x2::sui::SUI>",
+            "objectId": address(seed),
+            "version": "3",
+            "digest": digest.to_string(),
+        })
+    };
+
+    serde_json::from_value(json!({
+        "digest": digest.to_string(),
+        "transaction": {
+            "data": {
+                "messageVersion": "v1",
+                "transaction": {
+                    "kind": "ProgrammableTransaction",
+                    "inputs": [],
+                    "transactions": [],
+                },
+                "sender": sender,
+                "gasData": {
+                    "payment": [],
+                    "owner": sender,
+                    "price": "1000",
+                    "budget": "10000000",
+                },
+            },
+            "txSignatures": [],
+        },
+        "effects": {
+            "messageVersion": "v1",
+            "status": {"status": "success"},
+            "executedEpoch": "500",
+            "gasUsed": {
+                "computationCost": "1000000",
+                "storageCost": "2000000",
+                "storageRebate": "500000",
+                "nonRefundableStorageFee": "0",
+            },
+            "transactionDigest": digest.to_string(),
+            "gasObject": {
+                "owner": {"AddressOwner": sender},
+                "reference": {"objectId": address(0xee), "version": 2, "digest": digest.to_string()},
+            },
+            "dependencies": [],
+        },
+        "balanceChanges": [
+            balance(&sender, -3_002_500_000),
+            balance(&address(0xb2), 2_000_000_000),
+            balance(&address(0xc3), 1_000_000_000),
+        ],
+        "objectChanges": (1..=4).map(created).collect::<Vec<_>>(),
+    }))
+    .expect("fixture is a valid SuiTransactionBlockResponse")
+}
+
+fn parse(c: &mut Criterion) {
+    let config = Arc::new(Config::from_lookup(|_| None).expect("the defaults are valid"));
+    let client = SuiClient::new(config);
+    let digest = TransactionDigest::new([7; 32]);
+    let tx = transfer(digest);
+    let digest = digest.to_string();
+    let options = ExplainOptions::default();
+
+    // Fills the type label cache, as any earlier request would have
+    client.parse_transaction(&digest, &tx, &options).unwrap();
+
+    c.bench_function("parse_transfer", |b| {
+        b.iter(|| client.parse_transaction(&digest, &tx, &options).unwrap())
+    });
+}
+
+criterion_group!(benches, parse);
+criterion_main!(benches);
@@ -0,0 +1,137 @@
+use serde::Deserialize;
+use std::{collections::HashMap, str::FromStr};
+use sui_types::base_types::ObjectID;
+
+// Known abort codes from the Sui framework, (package, module, code, name)
+const BUILT_IN: &[(&str, &str, u64, &str)] = &[
+    (
+        "0x2",
+        "balance",
+        0,
+        "ENonZero: balance must be zero to destroy it",
+    ),
+    ("0x2", "balance", 1, "EOverflow: balance supply overflow"),
+    ("0x2", "balance", 2, "ENotEnough: insufficient balance"),
+    ("0x2", "coin", 0, "EBadWitness: invalid one-time witness"),
+    ("0x2", "coin", 1, "EInvalidArg: invalid argument"),
+    ("0x2", "coin", 2, "ENotEnough: insufficient coin balance"),
+    (
+        "0x2",
+        "dynamic_field",
+        0,
+        "EFieldAlreadyExists: dynamic field already exists",
+    ),
+    (
+        "0x2",
+        "dynamic_field",
+        1,
+        "EFieldDoesNotExist: dynamic field does not exist",
+    ),
+    (
+        "0x2",
+        "dynamic_field",
+        2,
+        "EFieldTypeMismatch: dynamic field has a different type",
+    ),
+];
+
+// One entry of the ABORT_CODES_PATH JSON file
+#[derive(Debug, Deserialize)]
+struct AbortCodeEntry {
+    package: String,
+    module: String,
+    code: u64,
+    message: String,
+}
+
+// Maps (package, module, abort code) to a readable reason. Extra codes are loaded from the
+// JSON array at ABORT_CODES_PATH, e.g. [{"package": "0x...", "module": "pool", "code": 3, "message": "ESlippageExceeded"}]
+pub struct AbortCodes {
+    messages: HashMap<(ObjectID, String, u64), String>,
+}
+
+impl AbortCodes {
+    pub fn new(path: Option<&str>) -> Self {
+        let mut messages = HashMap::new();
+
+        for (package, module, code, message) in BUILT_IN {
+            if let Ok(package) = ObjectID::from_str(package) {
+                messages.insert((package, module.to_string(), *code), message.to_string());
+            }
+        }
+
+        if let Some(path) = path {
+            match Self::load_file(path) {
+                Ok(entries) => {
+                    println!("Loaded {} abort codes from {}", entries.len(), path);
+                    for entry in entries {
+                        match ObjectID::from_str(&entry.package) {
+                            Ok(package) => {
+                                messages.insert((package, entry.module, entry.code), entry.message);
+                            }
+                            Err(_) => {
+                                eprintln!("Skipping abort code with bad package {}", entry.package)
+                            }
+                        }
+                    }
+                }
+                Err(e) => eprintln!("Failed to load abort codes from {}: {}", path, e),
+            }
+        }
+
+        Self { messages }
+    }
+
+    fn load_file(path: &str) -> Result<Vec<AbortCodeEntry>, String> {
+        let contents = std::fs::read_to_string(path).map_err(|e| e.to_string())?;
+        serde_json::from_str(&contents).map_err(|e| e.to_string())
+    }
+
+    //Turn a failed execution status into a readable reason, if it was a Move abort
+    pub fn describe(&self, error: &str) -> Option<String> {
+        let (address, module, code) = parse_move_abort(error)?;
+        let package = ObjectID::from_str(&address).ok();
+        let address = format!(
+            "0x{}",
+            address.trim_start_matches("0x").trim_start_matches('0')
+        );
+
+        let known = package.and_then(|p| self.messages.get(&(p, module.clone(), code)));
+        Some(match known {
+            Some(message) => format!(
+                "{} (abort code {} in {}::{})",
+                message, code, address, module
+            ),
+            None => format!("Aborted with code {} in {}::{}", code, address, module),
+        })
+    }
+}
+
+// Pull (address, module, code) out of the status text, which looks like
+// MoveAbort(MoveLocation { module: ModuleId { address: 00..02, name: Identifier("coin") }, ... }, 2) in command 0
+fn parse_move_abort(error: &str) -> Option<(String, String, u64)> {
+    let abort = &error[error.find("MoveAbort(")?..];
+
+    let address = after(abort, "address: ")?
+        .split([',', ' '])
+        .next()?
+        .to_string();
+    let module = after(abort, "name: Identifier(\"")?
+        .split('"')
+        .next()?
+        .to_string();
+
+    // The code comes right after the location struct closes
+    let code = abort[abort.rfind("}, ")? + 3..]
+        .split(')')
+        .next()?
+        .trim()
+        .parse()
+        .ok()?;
+
+    Some((address, module, code))
+}
+
+fn after<'a>(text: &'a str, pattern: &str) -> Option<&'a str> {
+    text.find(pattern).map(|i| &text[i + pattern.len()..])
+}
@@ -0,0 +1,52 @@
+use std::{collections::HashMap, str::FromStr};
+use sui_types::base_types::SuiAddress;
+
+// Display names for addresses a team already knows (treasury, DEX router, bridge...), loaded once
+// at startup from the JSON object at ADDRESS_LABELS_PATH, e.g. {"0x...": "DEX Router"}
+#[derive(Default)]
+pub struct AddressLabels {
+    labels: HashMap<SuiAddress, String>,
+}
+
+impl AddressLabels {
+    pub fn new(configured: Option<&str>) -> Self {
+        let path = configured.unwrap_or("address_labels.json");
+
+        // The default file is optional, so only complain when it exists but can't be used
+        let contents = match std::fs::read_to_string(path) {
+            Ok(contents) => contents,
+            Err(e) => {
+                if configured.is_some() {
+                    eprintln!("Failed to load address labels from {}: {}", path, e);
+                }
+                return Self::default();
+            }
+        };
+
+        let entries: HashMap<String, String> = match serde_json::from_str(&contents) {
+            Ok(entries) => entries,
+            Err(e) => {
+                eprintln!("Failed to parse address labels from {}: {}", path, e);
+                return Self::default();
+            }
+        };
+
+        let mut labels = HashMap::new();
+        for (address, label) in entries {
+            match SuiAddress::from_str(&address) {
+                Ok(address) => {
+                    labels.insert(address, label);
+                }
+                Err(_) => eprintln!("Skipping address label with bad address {}", address),
+            }
+        }
+
+        println!("Loaded {} address labels from {}", labels.len(), path);
+        Self { labels }
+    }
+
+    pub fn get(&self, address: &str) -> Option<&str> {
+        let address = SuiAddress::from_str(address).ok()?;
+        self.labels.get(&address).map(String::as_str)
+    }
+}
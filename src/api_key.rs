@@ -0,0 +1,61 @@
+use std::{collections::HashSet, sync::Arc};
+
+use axum::{
+    extract::{Request, State},
+    middleware::Next,
+    response::Response,
+};
+
+use crate::error::ExplainError;
+use crate::handlers;
+use crate::request_id::RequestId;
+
+pub const API_KEY_HEADER: &str = "x-api-key";
+
+// Keys accepted in X-API-Key, from the comma-separated API_KEYS setting. Empty means auth is off.
+#[derive(Debug, Clone, Default)]
+pub struct ApiKeys(Arc<HashSet<String>>);
+
+impl ApiKeys {
+    pub fn new(keys: HashSet<String>) -> Self {
+        if !keys.is_empty() {
+            println!("API key auth enabled ({} keys)", keys.len());
+        }
+        Self(Arc::new(keys))
+    }
+}
+
+//Reject /api/* requests without a known X-API-Key, health checks and the frontend stay open
+pub async fn require_api_key(
+    State(keys): State<ApiKeys>,
+    request: Request,
+    next: Next,
+) -> Response {
+    let path = request.uri().path();
+    let protected = path.starts_with("/api/") && path != "/api/health";
+    if keys.0.is_empty() || !protected {
+        return next.run(request).await;
+    }
+
+    let problem = match request
+        .headers()
+        .get(API_KEY_HEADER)
+        .and_then(|v| v.to_str().ok())
+    {
+        Some(key) if keys.0.contains(key) => None,
+        Some(_) => Some("invalid API key"),
+        None => Some("missing X-API-Key header"),
+    };
+    match problem {
+        None => next.run(request).await,
+        Some(reason) => {
+            // The request ID middleware wraps this one, so the ID is already set
+            let request_id = request
+                .extensions()
+                .get::<RequestId>()
+                .map(|id| id.0.clone());
+            let e = ExplainError::Unauthorized(reason.to_string());
+            handlers::rejection(e, request_id, None)
+        }
+    }
+}
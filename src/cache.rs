@@ -0,0 +1,147 @@
+use std::{
+    collections::{BTreeMap, HashMap},
+    sync::{Arc, Mutex},
+    time::{Duration, Instant},
+};
+use tokio::sync::{Mutex as AsyncMutex, OwnedMutexGuard};
+
+use crate::models::{ContextTransaction, ResponseMeta, SenderBalance, TransactionExplanation};
+
+// Entries kept per section before starting over, so memory stays bounded
+const MAX_ENTRIES: usize = 1_000;
+
+// Explanations are cached in two sections. The core explanation is built from finalized transaction
+// data that never changes, so it's kept until CACHE_CORE_TTL_SECS (forever when unset). Enrichments
+// describe the chain as it is now (Display fields, current balances, neighbouring transactions) and
+// expire after CACHE_ENRICHMENT_TTL_SECS (default 60). The two are merged on read.
+// Misses go through single_flight, so a burst of identical requests (typically client retries)
+// costs one round of RPC work and everyone else waits for its entry.
+pub struct ExplanationCache {
+    core: Mutex<HashMap<String, Entry<(TransactionExplanation, ResponseMeta)>>>,
+    enrichments: Mutex<HashMap<String, Entry<Enrichments>>>,
+    in_flight: Mutex<HashMap<String, Arc<AsyncMutex<()>>>>, //Cache key -> lock held while it's being built
+    core_ttl: Option<Duration>,
+    enrichment_ttl: Duration,
+}
+
+struct Entry<T> {
+    value: T,
+    stored: Instant,
+}
+
+// Everything layered on top of the core explanation that can change after the transaction
+#[derive(Debug, Clone, Default)]
+pub struct Enrichments {
+    pub display: BTreeMap<String, BTreeMap<String, String>>, //Object ID -> Display fields
+    pub sender_balances: Option<Vec<SenderBalance>>,
+    pub context: Vec<ContextTransaction>,
+    pub checkpoint_index: Option<usize>,
+}
+
+impl Enrichments {
+    pub fn apply(self, explanation: &mut TransactionExplanation) {
+        for change in &mut explanation.object_changes {
+            if let Some(fields) = self.display.get(&change.object_id) {
+                change.display = Some(fields.clone());
+            }
+        }
+        explanation.sender_balances = self.sender_balances;
+        explanation.context = self.context;
+        explanation.checkpoint_index = self.checkpoint_index;
+    }
+}
+
+impl ExplanationCache {
+    pub fn new(core_ttl: Option<Duration>, enrichment_ttl: Duration) -> Self {
+        Self {
+            core: Mutex::new(HashMap::new()),
+            enrichments: Mutex::new(HashMap::new()),
+            in_flight: Mutex::new(HashMap::new()),
+            core_ttl,
+            enrichment_ttl,
+        }
+    }
+
+    pub fn core(&self, key: &str) -> Option<(TransactionExplanation, ResponseMeta)> {
+        get(&self.core, key, self.core_ttl)
+    }
+
+    pub fn store_core(&self, key: &str, value: (TransactionExplanation, ResponseMeta)) {
+        insert(&self.core, key, value);
+    }
+
+    pub fn enrichments(&self, key: &str) -> Option<Enrichments> {
+        get(&self.enrichments, key, Some(self.enrichment_ttl))
+    }
+
+    pub fn store_enrichments(&self, key: &str, value: Enrichments) {
+        insert(&self.enrichments, key, value);
+    }
+
+    //Wait until nobody else is building the entry for this key. Check the cache again once this
+    //returns, the previous holder has usually just stored it. Hold the guard until your own store.
+    pub async fn single_flight(&self, key: &str) -> Flight<'_> {
+        let lock = self
+            .in_flight
+            .lock()
+            .unwrap()
+            .entry(key.to_string())
+            .or_default()
+            .clone();
+
+        Flight {
+            cache: self,
+            key: key.to_string(),
+            _guard: lock.lock_owned().await,
+        }
+    }
+}
+
+// Held while one request builds a cache entry, others asking for the same key queue behind it
+pub struct Flight<'a> {
+    cache: &'a ExplanationCache,
+    key: String,
+    _guard: OwnedMutexGuard<()>,
+}
+
+impl Drop for Flight<'_> {
+    fn drop(&mut self) {
+        let mut in_flight = self.cache.in_flight.lock().unwrap();
+        // Only the map and this guard still point at the lock: nobody is waiting, forget the key
+        if in_flight
+            .get(&self.key)
+            .is_some_and(|lock| Arc::strong_count(lock) <= 2)
+        {
+            in_flight.remove(&self.key);
+        }
+    }
+}
+
+fn get<T: Clone>(
+    entries: &Mutex<HashMap<String, Entry<T>>>,
+    key: &str,
+    ttl: Option<Duration>,
+) -> Option<T> {
+    let mut entries = entries.lock().unwrap();
+    let entry = entries.get(key)?;
+
+    if ttl.is_some_and(|ttl| entry.stored.elapsed() >= ttl) {
+        entries.remove(key);
+        return None;
+    }
+    Some(entry.value.clone())
+}
+
+fn insert<T>(entries: &Mutex<HashMap<String, Entry<T>>>, key: &str, value: T) {
+    let mut entries = entries.lock().unwrap();
+    if entries.len() >= MAX_ENTRIES {
+        entries.clear();
+    }
+    entries.insert(
+        key.to_string(),
+        Entry {
+            value,
+            stored: Instant::now(),
+        },
+    );
+}
@@ -0,0 +1,100 @@
+use std::collections::BTreeMap;
+
+use sui_json_rpc_types::{
+    SuiArgument, SuiCallArg, SuiCommand, SuiObjectArg, SuiProgrammableTransactionBlock,
+    SuiTransactionBlockKind,
+};
+use sui_types::base_types::ObjectID;
+
+use crate::models::MoveCall;
+
+// Decoding of programmable transaction blocks (PTBs)
+
+//Get the PTB out of a transaction kind, system transactions don't have one
+pub fn programmable(kind: &SuiTransactionBlockKind) -> Option<&SuiProgrammableTransactionBlock> {
+    match kind {
+        SuiTransactionBlockKind::ProgrammableTransaction(ptb) => Some(ptb),
+        _ => None,
+    }
+}
+
+pub fn command_kind(command: &SuiCommand) -> &'static str {
+    match command {
+        SuiCommand::MoveCall(_) => "MoveCall",
+        SuiCommand::TransferObjects(..) => "TransferObjects",
+        SuiCommand::SplitCoins(..) => "SplitCoins",
+        SuiCommand::MergeCoins(..) => "MergeCoins",
+        SuiCommand::Publish(_) => "Publish",
+        SuiCommand::Upgrade(..) => "Upgrade",
+        SuiCommand::MakeMoveVec(..) => "MakeMoveVec",
+    }
+}
+
+//Count commands by kind, e.g. {"MoveCall": 3, "SplitCoins": 2}
+pub fn command_summary(commands: &[SuiCommand]) -> BTreeMap<String, usize> {
+    let mut summary = BTreeMap::new();
+    for command in commands {
+        *summary
+            .entry(command_kind(command).to_string())
+            .or_insert(0) += 1;
+    }
+    summary
+}
+
+//The package an Upgrade command replaces, if the PTB upgrades one
+pub fn upgraded_package(commands: &[SuiCommand]) -> Option<ObjectID> {
+    commands.iter().find_map(|command| match command {
+        SuiCommand::Upgrade(_, _, package, _) => Some(*package),
+        _ => None,
+    })
+}
+
+//Shared objects the PTB takes as inputs, in input order
+pub fn shared_inputs(ptb: &SuiProgrammableTransactionBlock) -> Vec<ObjectID> {
+    ptb.inputs
+        .iter()
+        .filter_map(|input| match input {
+            SuiCallArg::Object(SuiObjectArg::SharedObject { object_id, .. }) => Some(*object_id),
+            _ => None,
+        })
+        .collect()
+}
+
+//Packages the PTB calls into, in call order with repeats
+pub fn called_packages(commands: &[SuiCommand]) -> Vec<ObjectID> {
+    commands
+        .iter()
+        .filter_map(|command| match command {
+            SuiCommand::MoveCall(call) => Some(call.package),
+            _ => None,
+        })
+        .collect()
+}
+
+//Every MoveCall command, in execution order
+pub fn move_calls(commands: &[SuiCommand]) -> Vec<MoveCall> {
+    commands
+        .iter()
+        .filter_map(|command| match command {
+            SuiCommand::MoveCall(call) => Some(MoveCall {
+                package: call.package.to_string(),
+                module: call.module.clone(),
+                function: call.function.clone(),
+                type_args: call.type_arguments.clone(),
+                args: call.arguments.iter().map(format_argument).collect(),
+                decoded_args: None,
+            }),
+            _ => None,
+        })
+        .collect()
+}
+
+// "Input(0)", "Result(1)", "NestedResult(1, 0)" or "GasCoin"
+fn format_argument(argument: &SuiArgument) -> String {
+    match argument {
+        SuiArgument::GasCoin => "GasCoin".to_string(),
+        SuiArgument::Input(i) => format!("Input({})", i),
+        SuiArgument::Result(i) => format!("Result({})", i),
+        SuiArgument::NestedResult(i, j) => format!("NestedResult({}, {})", i, j),
+    }
+}
@@ -0,0 +1,391 @@
+use std::{
+    collections::{HashMap, HashSet},
+    fmt,
+    str::FromStr,
+    time::Duration,
+};
+
+use crate::explorer::Explorer;
+use crate::format::{self, AddressFormat};
+
+const DEFAULT_RPC_URL: &str = "https://fullnode.mainnet.sui.io:443";
+
+// A request that takes longer than 10s is better retried on another endpoint, and 256 in-flight
+// requests per endpoint is the SDK's own default
+const DEFAULT_REQUEST_TIMEOUT_MS: u64 = 10_000;
+const DEFAULT_MAX_CONCURRENT_REQUESTS: usize = 256;
+
+// Each CoinMetadata lookup gets this long before the coin falls back to raw amounts
+const DEFAULT_COIN_METADATA_TIMEOUT_MS: u64 = 1_500;
+
+// Checkpoints come every few hundred milliseconds, a node whose latest one is older than this is behind
+const DEFAULT_NODE_MAX_LAG_MS: u64 = 30_000;
+
+const DEFAULT_ENRICHMENT_TTL_SECS: u64 = 60;
+
+// Everything but the transaction-bytes endpoints (/api/decode, /api/dry-run) only carries digests
+// and flags. Both limits replace axum's 2 MiB Json default on their routes, larger values work too.
+const DEFAULT_BODY_LIMIT_BYTES: usize = 64 * 1024;
+const DEFAULT_TX_BODY_LIMIT_BYTES: usize = 1024 * 1024;
+
+// Most digests one batch may ask for, and how many of them are explained at the same time
+const DEFAULT_BATCH_MAX_SIZE: usize = 100;
+const DEFAULT_BATCH_CONCURRENCY: usize = 8;
+
+// Every setting the server reads from the environment, loaded and validated once in main and handed
+// to the parts that need it. Unset variables take the defaults below, set but unusable ones are all
+// reported together so a bad deployment fails at startup instead of limping along on defaults.
+// README.md describes each of them.
+//
+//   RPC        SUI_RPC_URLS, SUI_HISTORY_RPC_URLS (comma-separated), SUI_RPC_REQUEST_TIMEOUT_MS,
+//              SUI_RPC_MAX_CONCURRENT_REQUESTS, SUI_RPC_HEADERS ("Name: value" pairs separated by ';'),
+//              SUI_RPC_USER_AGENT, COIN_METADATA_TIMEOUT_MS, NODE_MAX_LAG_MS
+//   Cache      CACHE_CORE_TTL_SECS (forever when unset), CACHE_ENRICHMENT_TTL_SECS
+//   Requests   BODY_LIMIT_BYTES, TX_BODY_LIMIT_BYTES, BATCH_MAX_SIZE, BATCH_CONCURRENCY, API_KEYS,
+//              DEFAULT_PRETTY
+//   Rendering  SUI_PRECISION, ADDRESS_FORMAT, EXPLORER
+//   Files      ABORT_CODES_PATH, ADDRESS_LABELS_PATH, BLOCKLIST_PATH, STATIC_DIR
+//   Safety     SAFETY_CHECKS ("off" disables them)
+#[derive(Debug, Clone)]
+pub struct Config {
+    pub rpc_urls: Vec<String>,
+    pub history_rpc_urls: Vec<String>, //Deep-history endpoints, empty when there are none
+    pub rpc_request_timeout: Duration,
+    pub rpc_max_concurrent_requests: usize,
+    pub rpc_headers: HashMap<String, String>, //Sent with every RPC request, User-Agent included
+    pub coin_metadata_timeout: Duration,
+    pub node_max_lag: Duration, //Latest checkpoint age past which check_node_lag flags the node
+    pub cache_core_ttl: Option<Duration>, //None keeps finalized explanations forever
+    pub cache_enrichment_ttl: Duration,
+    pub body_limit: usize,
+    pub tx_body_limit: usize,
+    pub batch_max_size: usize,
+    pub batch_concurrency: usize,
+    pub api_keys: HashSet<String>, //Empty means auth is off
+    pub default_pretty: bool,
+    pub sui_precision: usize,
+    pub address_format: AddressFormat,
+    pub explorer: Explorer,
+    pub abort_codes_path: Option<String>,
+    pub address_labels_path: Option<String>, //None still tries address_labels.json
+    pub blocklist_path: Option<String>,
+    pub static_dir: String,
+    pub safety_checks: bool,
+}
+
+// Every setting that couldn't be used, one per line
+#[derive(Debug)]
+pub struct ConfigError(Vec<String>);
+
+impl fmt::Display for ConfigError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "Invalid configuration:")?;
+        for problem in &self.0 {
+            writeln!(f, "  {}", problem)?;
+        }
+        Ok(())
+    }
+}
+
+impl Config {
+    pub fn from_env() -> Result<Self, ConfigError> {
+        Self::from_lookup(|name| std::env::var(name).ok())
+    }
+
+    // Settings from any source of variables, from_env reads the process environment
+    pub fn from_lookup(
+        lookup: impl Fn(&str) -> Option<String> + 'static,
+    ) -> Result<Self, ConfigError> {
+        let mut env = Env {
+            lookup: Box::new(lookup),
+            problems: vec![],
+        };
+
+        let mut rpc_headers = HashMap::new();
+        let headers = env.string("SUI_RPC_HEADERS").unwrap_or_default();
+        for pair in headers.split(';').filter(|pair| !pair.trim().is_empty()) {
+            match pair.split_once(':') {
+                Some((name, value)) if !name.trim().is_empty() => {
+                    rpc_headers.insert(name.trim().to_string(), value.trim().to_string());
+                }
+                _ => env.invalid("SUI_RPC_HEADERS", pair, "\"Name: value\""),
+            }
+        }
+        if let Some(user_agent) = env.string("SUI_RPC_USER_AGENT") {
+            rpc_headers.insert("User-Agent".to_string(), user_agent);
+        }
+
+        let mut rpc_urls = list(env.string("SUI_RPC_URLS"));
+        if rpc_urls.is_empty() {
+            rpc_urls.push(DEFAULT_RPC_URL.to_string());
+        }
+
+        let address_format = match env.string("ADDRESS_FORMAT") {
+            Some(value) => AddressFormat::parse(&value).unwrap_or_else(|| {
+                env.invalid(
+                    "ADDRESS_FORMAT",
+                    &value,
+                    "\"short\", \"full\" or \"<prefix>...<suffix>\"",
+                );
+                AddressFormat::default()
+            }),
+            None => AddressFormat::default(),
+        };
+        let explorer = match env.string("EXPLORER") {
+            Some(value) => Explorer::parse(&value).unwrap_or_else(|| {
+                env.invalid("EXPLORER", &value, "\"suivision\" or \"suiscan\"");
+                Explorer::default()
+            }),
+            None => Explorer::default(),
+        };
+
+        let sui_precision = env.number("SUI_PRECISION", format::DEFAULT_SUI_PRECISION);
+        if sui_precision > format::SUI_DECIMALS {
+            env.invalid(
+                "SUI_PRECISION",
+                &sui_precision.to_string(),
+                "at most 9 decimals",
+            );
+        }
+
+        let config = Config {
+            history_rpc_urls: list(env.string("SUI_HISTORY_RPC_URLS")),
+            rpc_urls,
+            rpc_request_timeout: Duration::from_millis(
+                env.positive("SUI_RPC_REQUEST_TIMEOUT_MS", DEFAULT_REQUEST_TIMEOUT_MS),
+            ),
+            rpc_max_concurrent_requests: env.positive(
+                "SUI_RPC_MAX_CONCURRENT_REQUESTS",
+                DEFAULT_MAX_CONCURRENT_REQUESTS,
+            ),
+            rpc_headers,
+            coin_metadata_timeout: Duration::from_millis(
+                env.positive("COIN_METADATA_TIMEOUT_MS", DEFAULT_COIN_METADATA_TIMEOUT_MS),
+            ),
+            node_max_lag: Duration::from_millis(
+                env.positive("NODE_MAX_LAG_MS", DEFAULT_NODE_MAX_LAG_MS),
+            ),
+            cache_core_ttl: env
+                .optional_number("CACHE_CORE_TTL_SECS")
+                .map(Duration::from_secs),
+            cache_enrichment_ttl: Duration::from_secs(
+                env.number("CACHE_ENRICHMENT_TTL_SECS", DEFAULT_ENRICHMENT_TTL_SECS),
+            ),
+            body_limit: env.positive("BODY_LIMIT_BYTES", DEFAULT_BODY_LIMIT_BYTES),
+            tx_body_limit: env.positive("TX_BODY_LIMIT_BYTES", DEFAULT_TX_BODY_LIMIT_BYTES),
+            batch_max_size: env.positive("BATCH_MAX_SIZE", DEFAULT_BATCH_MAX_SIZE),
+            batch_concurrency: env.positive("BATCH_CONCURRENCY", DEFAULT_BATCH_CONCURRENCY),
+            api_keys: list(env.string("API_KEYS")).into_iter().collect(),
+            default_pretty: env.flag("DEFAULT_PRETTY"),
+            sui_precision,
+            address_format,
+            explorer,
+            abort_codes_path: env.string("ABORT_CODES_PATH"),
+            address_labels_path: env.string("ADDRESS_LABELS_PATH"),
+            blocklist_path: env.string("BLOCKLIST_PATH"),
+            static_dir: env
+                .string("STATIC_DIR")
+                .unwrap_or_else(|| "static".to_string()),
+            safety_checks: env.string("SAFETY_CHECKS").as_deref() != Some("off"),
+        };
+
+        if env.problems.is_empty() {
+            Ok(config)
+        } else {
+            Err(ConfigError(env.problems))
+        }
+    }
+}
+
+// Reads variables and collects what's wrong with them instead of stopping at the first problem
+struct Env {
+    lookup: Box<dyn Fn(&str) -> Option<String>>,
+    problems: Vec<String>,
+}
+
+impl Env {
+    fn string(&self, name: &str) -> Option<String> {
+        (self.lookup)(name)
+    }
+
+    fn invalid(&mut self, name: &str, value: &str, expected: &str) {
+        self.problems.push(format!(
+            "{}={:?} is not valid, expected {}",
+            name, value, expected
+        ));
+    }
+
+    fn optional_number<T: FromStr>(&mut self, name: &str) -> Option<T> {
+        let value = self.string(name)?;
+        match value.trim().parse() {
+            Ok(number) => Some(number),
+            Err(_) => {
+                self.invalid(name, &value, "a whole number");
+                None
+            }
+        }
+    }
+
+    fn number<T: FromStr>(&mut self, name: &str, default: T) -> T {
+        self.optional_number(name).unwrap_or(default)
+    }
+
+    // Limits, timeouts and concurrency where 0 would stall or reject everything
+    fn positive<T: FromStr + PartialOrd + Default + fmt::Display>(
+        &mut self,
+        name: &str,
+        default: T,
+    ) -> T {
+        match self.optional_number(name) {
+            Some(number) if number > T::default() => number,
+            Some(number) => {
+                self.invalid(name, &number.to_string(), "a number above 0");
+                default
+            }
+            None => default,
+        }
+    }
+
+    fn flag(&mut self, name: &str) -> bool {
+        match self.string(name).as_deref() {
+            None => false,
+            Some("true") | Some("1") => true,
+            Some("false") | Some("0") => false,
+            Some(value) => {
+                self.invalid(name, value, "true or false");
+                false
+            }
+        }
+    }
+}
+
+// "a, b,,c" -> ["a", "b", "c"]
+fn list(value: Option<String>) -> Vec<String> {
+    value
+        .unwrap_or_default()
+        .split(',')
+        .map(str::trim)
+        .filter(|item| !item.is_empty())
+        .map(str::to_string)
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn load(vars: &[(&str, &str)]) -> Result<Config, ConfigError> {
+        let vars: HashMap<String, String> = vars
+            .iter()
+            .map(|(name, value)| (name.to_string(), value.to_string()))
+            .collect();
+        Config::from_lookup(move |name| vars.get(name).cloned())
+    }
+
+    fn problems(vars: &[(&str, &str)]) -> Vec<String> {
+        load(vars).expect_err("the settings are invalid").0
+    }
+
+    #[test]
+    fn unset_variables_take_the_defaults() {
+        let config = load(&[]).unwrap();
+        assert_eq!(config.rpc_urls, vec![DEFAULT_RPC_URL]);
+        assert!(config.history_rpc_urls.is_empty());
+        assert_eq!(
+            config.rpc_request_timeout,
+            Duration::from_millis(DEFAULT_REQUEST_TIMEOUT_MS)
+        );
+        assert_eq!(config.cache_core_ttl, None);
+        assert_eq!(config.body_limit, DEFAULT_BODY_LIMIT_BYTES);
+        assert_eq!(config.tx_body_limit, DEFAULT_TX_BODY_LIMIT_BYTES);
+        assert_eq!(config.batch_max_size, DEFAULT_BATCH_MAX_SIZE);
+        assert_eq!(config.batch_concurrency, DEFAULT_BATCH_CONCURRENCY);
+        assert_eq!(config.sui_precision, format::DEFAULT_SUI_PRECISION);
+        assert!(config.api_keys.is_empty());
+        assert!(config.rpc_headers.is_empty());
+        assert!(!config.default_pretty);
+        assert!(config.safety_checks);
+    }
+
+    #[test]
+    fn reads_set_variables() {
+        let config = load(&[
+            ("SUI_RPC_URLS", "https://a.example, https://b.example"),
+            (
+                "SUI_RPC_HEADERS",
+                "X-Team: explorer; Authorization: Bearer t",
+            ),
+            ("SUI_RPC_USER_AGENT", "sui-readable"),
+            ("CACHE_CORE_TTL_SECS", "300"),
+            ("DEFAULT_PRETTY", "1"),
+            ("SAFETY_CHECKS", "off"),
+        ])
+        .unwrap();
+        assert_eq!(
+            config.rpc_urls,
+            vec!["https://a.example", "https://b.example"]
+        );
+        assert_eq!(config.rpc_headers.len(), 3);
+        assert_eq!(config.rpc_headers["Authorization"], "Bearer t");
+        assert_eq!(config.rpc_headers["User-Agent"], "sui-readable");
+        assert_eq!(config.cache_core_ttl, Some(Duration::from_secs(300)));
+        assert!(config.default_pretty);
+        assert!(!config.safety_checks);
+    }
+
+    #[test]
+    fn every_bad_value_is_reported_together() {
+        let problems = problems(&[
+            ("BATCH_MAX_SIZE", "lots"),
+            ("DEFAULT_PRETTY", "maybe"),
+            ("EXPLORER", "etherscan"),
+            ("ADDRESS_FORMAT", "0...0"),
+        ]);
+        assert_eq!(problems.len(), 4, "{:?}", problems);
+        for name in [
+            "BATCH_MAX_SIZE",
+            "DEFAULT_PRETTY",
+            "EXPLORER",
+            "ADDRESS_FORMAT",
+        ] {
+            assert!(problems.iter().any(|p| p.starts_with(name)), "{}", name);
+        }
+    }
+
+    #[test]
+    fn limits_and_timeouts_must_be_above_zero() {
+        let problems = problems(&[
+            ("BATCH_CONCURRENCY", "0"),
+            ("SUI_RPC_REQUEST_TIMEOUT_MS", "0"),
+        ]);
+        assert_eq!(
+            problems,
+            vec![
+                "SUI_RPC_REQUEST_TIMEOUT_MS=\"0\" is not valid, expected a number above 0",
+                "BATCH_CONCURRENCY=\"0\" is not valid, expected a number above 0",
+            ]
+        );
+    }
+
+    #[test]
+    fn precision_stops_at_one_mist() {
+        assert!(load(&[("SUI_PRECISION", "9")]).is_ok());
+        assert_eq!(
+            problems(&[("SUI_PRECISION", "10")]),
+            vec!["SUI_PRECISION=\"10\" is not valid, expected at most 9 decimals"]
+        );
+    }
+
+    #[test]
+    fn malformed_headers_are_reported() {
+        assert_eq!(
+            problems(&[(
+                "SUI_RPC_HEADERS",
+                "X-Team: explorer; Authorization Bearer t;"
+            )]),
+            vec![
+                "SUI_RPC_HEADERS=\" Authorization Bearer t\" is not valid, expected \"Name: value\""
+            ]
+        );
+    }
+}
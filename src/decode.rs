@@ -0,0 +1,134 @@
+use base64::{Engine, engine::general_purpose::STANDARD};
+use sui_json_rpc_types::SuiCommand;
+use sui_types::transaction::{
+    CallArg, ObjectArg, SenderSignedData, TransactionData, TransactionDataAPI, TransactionKind,
+};
+
+use crate::commands;
+use crate::error::ExplainError;
+use crate::explorer::Explorer;
+use crate::models::{DecodedTransaction, GasInfo, TransactionExplanation};
+use crate::sui_client::NETWORK;
+use crate::system_objects;
+
+// Offline decoding of transaction bytes, no RPC involved
+
+//Accept base64 BCS of either a signed transaction (SenderSignedData) or bare TransactionData
+pub fn decode_transaction_bytes(encoded: &str) -> Result<TransactionData, ExplainError> {
+    let bytes = STANDARD
+        .decode(encoded.trim())
+        .map_err(|e| ExplainError::InvalidTransactionBytes(format!("not valid base64: {}", e)))?;
+
+    if let Ok(signed) = bcs::from_bytes::<SenderSignedData>(&bytes) {
+        return Ok(signed.transaction_data().clone());
+    }
+
+    bcs::from_bytes::<TransactionData>(&bytes).map_err(|e| {
+        ExplainError::InvalidTransactionBytes(format!(
+            "neither SenderSignedData nor TransactionData: {}",
+            e
+        ))
+    })
+}
+
+//Pre-execution view of a transaction: who sends it, how gas is paid and what it will run
+pub fn describe_transaction_data(tx_data: &TransactionData) -> DecodedTransaction {
+    let gas = tx_data.gas_data();
+
+    let commands: Vec<SuiCommand> = match tx_data.kind() {
+        TransactionKind::ProgrammableTransaction(ptb) => {
+            ptb.commands.iter().cloned().map(SuiCommand::from).collect()
+        }
+        _ => vec![],
+    };
+
+    DecodedTransaction {
+        sender: tx_data.sender().to_string(),
+        gas: GasInfo {
+            owner: gas.owner.to_string(),
+            budget: gas.budget,
+            price: gas.price,
+            payment: gas
+                .payment
+                .iter()
+                .map(|(id, _, _)| id.to_string())
+                .collect(),
+        },
+        is_programmable: matches!(tx_data.kind(), TransactionKind::ProgrammableTransaction(_)),
+        command_summary: commands::command_summary(&commands),
+        commands: commands
+            .iter()
+            .map(commands::command_kind)
+            .map(str::to_string)
+            .collect(),
+        calls: commands::move_calls(&commands),
+    }
+}
+
+//Explain transaction data built in-process (e.g. with the SDK's PTB builder) before it's signed or sent.
+//Only what the data itself says is filled in: kind, sender, sponsor, commands, shared inputs and the
+//called packages. Status, gas used and every change stay empty since nothing has executed. There's
+//no config here, so addresses stay whole and links go to the default explorer. The server doesn't
+//call this, it's the entry point for embedding the explainer as a library (see lib.rs).
+pub fn explain_transaction_data(tx_data: &TransactionData) -> TransactionExplanation {
+    let decoded = describe_transaction_data(tx_data);
+    let sender = decoded.sender.clone();
+    let sponsor = Some(decoded.gas.owner.clone()).filter(|owner| *owner != sender);
+
+    let actions: Vec<String> = decoded
+        .calls
+        .iter()
+        .map(|call| format!("Calls {}::{}", call.module, call.function))
+        .collect();
+    let commands: Vec<String> = decoded
+        .command_summary
+        .iter()
+        .map(|(kind, count)| format!("{} {}", count, kind))
+        .collect();
+    let summary = if commands.is_empty() {
+        "Not executed yet, no programmable commands".to_string()
+    } else {
+        format!("Not executed yet, runs {}", commands.join(", "))
+    };
+
+    // Same as parse_transaction: shared inputs with system objects named, every package called
+    let shared_objects = match tx_data.kind() {
+        TransactionKind::ProgrammableTransaction(ptb) => ptb
+            .inputs
+            .iter()
+            .filter_map(|input| match input {
+                CallArg::Object(ObjectArg::SharedObject { id, .. }) => {
+                    let id = id.to_string();
+                    Some(system_objects::label(&id).unwrap_or(id))
+                }
+                _ => None,
+            })
+            .collect(),
+        _ => vec![],
+    };
+    let mut packages: Vec<String> = decoded
+        .calls
+        .iter()
+        .map(|call| call.package.clone())
+        .collect();
+    packages.sort();
+    packages.dedup();
+
+    let mut explanation = TransactionExplanation {
+        digest: tx_data.digest().to_string(),
+        kind: tx_data.kind().name().to_string(),
+        sender: Some(sender.clone()),
+        signers: vec![sender],
+        sponsor,
+        data_available: true,
+        status: "Not executed".to_string(),
+        command_summary: decoded.command_summary,
+        shared_objects,
+        packages: packages.clone(),
+        actions,
+        summary,
+        ..Default::default()
+    };
+    explanation.links = Explorer::default().links(NETWORK, &explanation, &packages);
+    explanation
+}
@@ -0,0 +1,119 @@
+use std::collections::BTreeMap;
+
+use crate::models::{ChangedEntry, ListDiff, TransactionDiff, TransactionExplanation, ValueDiff};
+
+//Compare two explanations field by field. "added" entries only appear in the right transaction, "removed" only in the left.
+pub fn diff_explanations(
+    left: &TransactionExplanation,
+    right: &TransactionExplanation,
+) -> TransactionDiff {
+    // Object changes are matched by object ID
+    let object_changes = diff_lists(
+        &left.object_changes,
+        &right.object_changes,
+        |change| change.object_id.clone(),
+        |l, r| {
+            l.change_type != r.change_type || l.object_type != r.object_type || l.owner != r.owner
+        },
+    );
+
+    // Balance changes are matched by who and which coin, by full type since two coins can share a label
+    let balance_changes = diff_lists(
+        &left.balance_changes,
+        &right.balance_changes,
+        |change| format!("{}|{}|{}", change.owner, change.full_type, change.is_gas),
+        |l, r| l.amount != r.amount,
+    );
+
+    TransactionDiff {
+        left_digest: left.digest.clone(),
+        right_digest: right.digest.clone(),
+        status: value_diff(&left.status, &right.status),
+        gas_used: value_diff(&left.gas_used, &right.gas_used),
+        gas_delta: right.gas_used as i128 - left.gas_used as i128,
+        object_changes,
+        balance_changes,
+    }
+}
+
+fn value_diff<T: Clone + PartialEq>(left: &T, right: &T) -> ValueDiff<T> {
+    ValueDiff {
+        left: left.clone(),
+        right: right.clone(),
+        changed: left != right,
+    }
+}
+
+fn diff_lists<T: Clone>(
+    left: &[T],
+    right: &[T],
+    key: impl Fn(&T) -> String,
+    differs: impl Fn(&T, &T) -> bool,
+) -> ListDiff<T> {
+    let left_by_key: BTreeMap<String, &T> = left.iter().map(|item| (key(item), item)).collect();
+    let right_by_key: BTreeMap<String, &T> = right.iter().map(|item| (key(item), item)).collect();
+
+    let mut diff = ListDiff {
+        added: vec![],
+        removed: vec![],
+        changed: vec![],
+    };
+
+    for (k, l) in &left_by_key {
+        match right_by_key.get(k) {
+            None => diff.removed.push((*l).clone()),
+            Some(r) if differs(l, r) => diff.changed.push(ChangedEntry {
+                left: (*l).clone(),
+                right: (*r).clone(),
+            }),
+            Some(_) => {}
+        }
+    }
+
+    for (k, r) in &right_by_key {
+        if !left_by_key.contains_key(k) {
+            diff.added.push((*r).clone());
+        }
+    }
+
+    diff
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::BalanceChange;
+
+    fn usdc(full_type: &str, amount: i128) -> BalanceChange {
+        BalanceChange {
+            owner: "0xa1".to_string(),
+            coin_type: "USDC".to_string(),
+            full_type: full_type.to_string(),
+            amount,
+            direction: "Received".to_string(),
+            amount_readable: format!("+{}", amount),
+            decimals_source: "fallback".to_string(),
+            is_gas: false,
+            is_sponsor: false,
+        }
+    }
+
+    #[test]
+    fn coins_sharing_a_label_are_kept_apart() {
+        let real = "0xdba3::usdc::USDC";
+        let fake = "0xbad::usdc::USDC";
+        let left = TransactionExplanation {
+            balance_changes: vec![usdc(real, 5), usdc(fake, 5)],
+            ..Default::default()
+        };
+        let right = TransactionExplanation {
+            balance_changes: vec![usdc(real, 5)],
+            ..Default::default()
+        };
+
+        let diff = diff_explanations(&left, &right);
+        assert_eq!(diff.balance_changes.removed.len(), 1);
+        assert_eq!(diff.balance_changes.removed[0].full_type, fake);
+        assert!(diff.balance_changes.changed.is_empty());
+    }
+}
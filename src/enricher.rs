@@ -0,0 +1,23 @@
+use futures::future::BoxFuture;
+use sui_json_rpc_types::SuiTransactionBlockResponse;
+
+use crate::models::TransactionExplanation;
+
+// Custom enrichment that runs after the core parse, with the raw response at hand: internal labels,
+// custom categories, extra warnings. Enrichers run in registration order wherever a transaction is
+// explained: fetched transactions, dry runs, history and context entries.
+//
+// The safety checks are the only built-in enricher. Validator names, coin decimals, coin flow and
+// the swap and staking descriptions feed the summary, so they stay part of the parse, and the node
+// lag check is about the serving node rather than the transaction.
+//
+// For fetched transactions, what an enricher adds is kept with the core explanation in the core
+// cache (CACHE_CORE_TTL_SECS, forever by default), not with the expiring enrichments. So it must only
+// depend on the transaction itself, not on the chain as it is now.
+pub trait ExplanationEnricher: Send + Sync {
+    fn enrich<'a>(
+        &'a self,
+        explanation: &'a mut TransactionExplanation,
+        raw: &'a SuiTransactionBlockResponse,
+    ) -> BoxFuture<'a, ()>;
+}
@@ -0,0 +1,189 @@
+use axum::http::StatusCode;
+use thiserror::Error;
+
+// Everything that can go wrong while explaining a transaction
+#[derive(Debug, Error)]
+pub enum ExplainError {
+    #[error("Invalid transaction digest format: {0}")]
+    InvalidDigest(String),
+
+    #[error("Invalid batch: {0}")]
+    InvalidBatch(String),
+
+    #[error("Invalid transaction bytes: {0}")]
+    InvalidTransactionBytes(String),
+
+    #[error("{0} not found")]
+    NotFound(String), //What was looked up, e.g. "Transaction 5Dg..." or "Package 0x2"
+
+    #[error("Invalid object ID format: {0}")]
+    InvalidObjectId(String),
+
+    #[error("Invalid SUI amount: {0}")]
+    InvalidAmount(String),
+
+    #[error("Invalid address format: {0}")]
+    InvalidAddress(String),
+
+    #[error("Invalid time range: {0}")]
+    InvalidTimeRange(String),
+
+    #[error("Object not found: {0}")]
+    ObjectNotFound(String),
+
+    #[error("Transaction {0} is not indexed yet, it may still be pending")]
+    Pending(String),
+
+    #[error("Transaction {0} data has been pruned from the node")]
+    DataPruned(String),
+
+    #[error("Original bytes of transaction {0} can't be reconstructed")]
+    BytesUnavailable(String),
+
+    #[error("No API route for {0}")]
+    RouteNotFound(String),
+
+    #[error("Method {0} is not allowed on {1}")]
+    MethodNotAllowed(String, String),
+
+    #[error("Unauthorized: {0}")]
+    Unauthorized(String),
+
+    #[error("Sui RPC request timed out")]
+    RpcTimeout,
+
+    #[error("Rate limited by the Sui RPC node")]
+    RateLimited,
+
+    #[error("Failed to connect to Sui: {0}")]
+    Connection(String),
+
+    #[error("RPC host of {0} could not be resolved, check the network URL")]
+    HostUnresolved(String),
+
+    #[error("TLS handshake with {0} failed, check the URL scheme and the server's certificate")]
+    TlsFailed(String),
+
+    #[error("{0} refused the connection, check the host and port and that the node is running")]
+    ConnectionRefused(String),
+
+    #[error("Connecting to {0} timed out, the node may be down or behind a firewall")]
+    ConnectTimeout(String),
+
+    #[error("Failed to fetch transaction from Sui: {0}")]
+    Rpc(String),
+}
+
+impl ExplainError {
+    //Sort an SDK error into one of our categories. The SDK doesn't type these, so go by the message.
+    //`subject` names what was asked for and ends up in the not found message.
+    pub fn from_rpc(e: sui_sdk::error::Error, subject: &str) -> Self {
+        let message = e.to_string();
+        let lower = message.to_lowercase();
+
+        if lower.contains("could not find") || lower.contains("not found") {
+            ExplainError::NotFound(subject.to_string())
+        } else if lower.contains("timed out") || lower.contains("timeout") {
+            ExplainError::RpcTimeout
+        } else if lower.contains("429") || lower.contains("too many requests") {
+            ExplainError::RateLimited
+        } else {
+            ExplainError::Rpc(message)
+        }
+    }
+
+    //Sort a failure to reach an endpoint by what went wrong, so a misconfigured URL gets an actionable
+    //message. Like from_rpc this goes by the message, the underlying hyper/rustls errors aren't exposed.
+    pub fn from_connect(e: sui_sdk::error::Error, url: &str) -> Self {
+        let message = e.to_string();
+        let lower = message.to_lowercase();
+
+        if lower.contains("dns error")
+            || lower.contains("failed to lookup address")
+            || lower.contains("name or service not known")
+            || lower.contains("no such host")
+        {
+            ExplainError::HostUnresolved(url.to_string())
+        } else if lower.contains("tls") || lower.contains("certificate") || lower.contains("ssl") {
+            ExplainError::TlsFailed(url.to_string())
+        } else if lower.contains("connection refused") {
+            ExplainError::ConnectionRefused(url.to_string())
+        } else if lower.contains("timed out") || lower.contains("timeout") {
+            ExplainError::ConnectTimeout(url.to_string())
+        } else {
+            ExplainError::Connection(message)
+        }
+    }
+
+    // Worth retrying on another RPC endpoint, as opposed to a problem with the request itself
+    pub fn is_transient(&self) -> bool {
+        matches!(
+            self,
+            ExplainError::RpcTimeout
+                | ExplainError::RateLimited
+                | ExplainError::Connection(_)
+                | ExplainError::HostUnresolved(_)
+                | ExplainError::TlsFailed(_)
+                | ExplainError::ConnectionRefused(_)
+                | ExplainError::ConnectTimeout(_)
+                | ExplainError::Rpc(_)
+        )
+    }
+
+    // Stable code clients can branch on, unlike the message
+    pub fn code(&self) -> &'static str {
+        match self {
+            ExplainError::InvalidDigest(_) => "INVALID_DIGEST",
+            ExplainError::InvalidBatch(_) => "INVALID_BATCH",
+            ExplainError::InvalidTransactionBytes(_) => "INVALID_TRANSACTION_BYTES",
+            ExplainError::NotFound(_) => "NOT_FOUND",
+            ExplainError::InvalidObjectId(_) => "INVALID_OBJECT_ID",
+            ExplainError::InvalidAmount(_) => "INVALID_AMOUNT",
+            ExplainError::InvalidAddress(_) => "INVALID_ADDRESS",
+            ExplainError::InvalidTimeRange(_) => "INVALID_TIME_RANGE",
+            ExplainError::ObjectNotFound(_) => "OBJECT_NOT_FOUND",
+            ExplainError::Pending(_) => "PENDING",
+            ExplainError::DataPruned(_) => "DATA_PRUNED",
+            ExplainError::BytesUnavailable(_) => "BYTES_UNAVAILABLE",
+            ExplainError::RouteNotFound(_) => "ROUTE_NOT_FOUND",
+            ExplainError::MethodNotAllowed(..) => "METHOD_NOT_ALLOWED",
+            ExplainError::Unauthorized(_) => "UNAUTHORIZED",
+            ExplainError::RpcTimeout => "RPC_TIMEOUT",
+            ExplainError::RateLimited => "RATE_LIMITED",
+            ExplainError::Connection(_) => "CONNECTION_FAILED",
+            ExplainError::HostUnresolved(_) => "RPC_HOST_UNRESOLVED",
+            ExplainError::TlsFailed(_) => "RPC_TLS_FAILED",
+            ExplainError::ConnectionRefused(_) => "RPC_CONNECTION_REFUSED",
+            ExplainError::ConnectTimeout(_) => "RPC_CONNECT_TIMEOUT",
+            ExplainError::Rpc(_) => "RPC_ERROR",
+        }
+    }
+
+    pub fn status(&self) -> StatusCode {
+        match self {
+            ExplainError::InvalidDigest(_) => StatusCode::BAD_REQUEST,
+            ExplainError::InvalidBatch(_) => StatusCode::BAD_REQUEST,
+            ExplainError::InvalidTransactionBytes(_) => StatusCode::BAD_REQUEST,
+            ExplainError::NotFound(_) => StatusCode::NOT_FOUND,
+            ExplainError::InvalidObjectId(_) => StatusCode::BAD_REQUEST,
+            ExplainError::InvalidAmount(_) => StatusCode::BAD_REQUEST,
+            ExplainError::InvalidAddress(_) => StatusCode::BAD_REQUEST,
+            ExplainError::InvalidTimeRange(_) => StatusCode::BAD_REQUEST,
+            ExplainError::ObjectNotFound(_) => StatusCode::NOT_FOUND,
+            ExplainError::Pending(_) => StatusCode::ACCEPTED,
+            ExplainError::DataPruned(_) => StatusCode::GONE,
+            ExplainError::BytesUnavailable(_) => StatusCode::UNPROCESSABLE_ENTITY,
+            ExplainError::RouteNotFound(_) => StatusCode::NOT_FOUND,
+            ExplainError::MethodNotAllowed(..) => StatusCode::METHOD_NOT_ALLOWED,
+            ExplainError::Unauthorized(_) => StatusCode::UNAUTHORIZED,
+            ExplainError::RpcTimeout => StatusCode::GATEWAY_TIMEOUT,
+            ExplainError::RateLimited => StatusCode::TOO_MANY_REQUESTS,
+            ExplainError::Connection(_) => StatusCode::SERVICE_UNAVAILABLE,
+            ExplainError::HostUnresolved(_) => StatusCode::BAD_GATEWAY,
+            ExplainError::TlsFailed(_) => StatusCode::BAD_GATEWAY,
+            ExplainError::ConnectionRefused(_) => StatusCode::SERVICE_UNAVAILABLE,
+            ExplainError::ConnectTimeout(_) => StatusCode::GATEWAY_TIMEOUT,
+            ExplainError::Rpc(_) => StatusCode::BAD_GATEWAY,
+        }
+    }
+}
@@ -0,0 +1,83 @@
+use std::collections::BTreeMap;
+
+use crate::models::{ExplorerLinks, TransactionExplanation};
+
+// Block explorers we can link to, picked with the EXPLORER env var ("suivision" or "suiscan")
+#[derive(Debug, Clone, Copy, Default)]
+pub enum Explorer {
+    #[default]
+    SuiVision,
+    SuiScan,
+}
+
+impl Explorer {
+    pub fn parse(name: &str) -> Option<Self> {
+        match name.trim() {
+            "suivision" => Some(Explorer::SuiVision),
+            "suiscan" => Some(Explorer::SuiScan),
+            _ => None,
+        }
+    }
+
+    //Build links for the transaction, its sender and every object/package it touched
+    pub fn links(
+        &self,
+        network: &str,
+        explanation: &TransactionExplanation,
+        packages: &[String],
+    ) -> ExplorerLinks {
+        let objects: BTreeMap<String, String> = explanation
+            .object_changes
+            .iter()
+            .filter(|change| change.object_id != "Unknown")
+            .map(|change| {
+                (
+                    change.object_id.clone(),
+                    self.url(network, "object", &change.object_id),
+                )
+            })
+            .collect();
+
+        let packages: BTreeMap<String, String> = packages
+            .iter()
+            .map(|id| (id.clone(), self.url(network, "package", id)))
+            .collect();
+
+        ExplorerLinks {
+            transaction: self.url(network, "transaction", &explanation.digest),
+            sender: explanation
+                .sender
+                .as_ref()
+                .map(|sender| self.url(network, "account", sender)),
+            objects,
+            packages,
+        }
+    }
+
+    fn url(&self, network: &str, kind: &str, id: &str) -> String {
+        match self {
+            // suivision.xyz is mainnet, other networks live on a subdomain
+            Explorer::SuiVision => {
+                let host = if network == "mainnet" {
+                    "suivision.xyz".to_string()
+                } else {
+                    format!("{}.suivision.xyz", network)
+                };
+                let path = match kind {
+                    "transaction" => "txblock",
+                    other => other,
+                };
+                format!("https://{}/{}/{}", host, path, id)
+            }
+            // suiscan.xyz puts the network in the path and shows packages as objects
+            Explorer::SuiScan => {
+                let path = match kind {
+                    "transaction" => "tx",
+                    "package" => "object",
+                    other => other,
+                };
+                format!("https://suiscan.xyz/{}/{}/{}", network, path, id)
+            }
+        }
+    }
+}
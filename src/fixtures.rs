@@ -0,0 +1,177 @@
+// Test doubles for transaction lookups, so handler and parser tests run without a network
+
+use futures::{FutureExt, future::BoxFuture};
+use serde_json::{Value, json};
+use std::{
+    collections::HashMap,
+    sync::{
+        Arc,
+        atomic::{AtomicUsize, Ordering},
+    },
+    time::Duration,
+};
+use sui_json_rpc_types::{SuiTransactionBlockResponse, SuiTransactionBlockResponseOptions};
+use sui_types::digests::TransactionDigest;
+
+use crate::config::Config;
+use crate::error::ExplainError;
+use crate::rpc::{RpcPool, TxFetcher};
+use crate::sui_client::SuiClient;
+
+// Answers with canned responses. Any other digest is not found, or fails with `failure` when set.
+// Keeps count of how many fetches ran at once, for tests of concurrency limits.
+#[derive(Default)]
+pub struct MockFetcher {
+    responses: HashMap<TransactionDigest, SuiTransactionBlockResponse>,
+    failure: Option<fn() -> ExplainError>,
+    delay: Duration, //How long each fetch takes, to keep several in flight at once
+    in_flight: AtomicUsize,
+    max_in_flight: AtomicUsize, //Most fetches seen running at the same time
+}
+
+impl MockFetcher {
+    pub fn with_response(mut self, response: SuiTransactionBlockResponse) -> Self {
+        self.responses.insert(response.digest, response);
+        self
+    }
+
+    pub fn failing(mut self, failure: fn() -> ExplainError) -> Self {
+        self.failure = Some(failure);
+        self
+    }
+
+    pub fn with_delay(mut self, delay: Duration) -> Self {
+        self.delay = delay;
+        self
+    }
+
+    pub fn max_in_flight(&self) -> usize {
+        self.max_in_flight.load(Ordering::SeqCst)
+    }
+}
+
+impl TxFetcher for MockFetcher {
+    fn fetch<'a>(
+        &'a self,
+        digest: TransactionDigest,
+        _options: &'a SuiTransactionBlockResponseOptions,
+    ) -> BoxFuture<'a, Result<(SuiTransactionBlockResponse, String), ExplainError>> {
+        async move {
+            let running = self.in_flight.fetch_add(1, Ordering::SeqCst) + 1;
+            self.max_in_flight.fetch_max(running, Ordering::SeqCst);
+            tokio::time::sleep(self.delay).await;
+            self.in_flight.fetch_sub(1, Ordering::SeqCst);
+
+            match (self.responses.get(&digest), self.failure) {
+                (Some(response), _) => Ok((response.clone(), "mock".to_string())),
+                (None, Some(failure)) => Err(failure()),
+                (None, None) => Err(ExplainError::NotFound(format!("Transaction {}", digest))),
+            }
+        }
+        .boxed()
+    }
+}
+
+// The defaults every unset variable falls back to
+pub fn test_config() -> Config {
+    Config::from_lookup(|_| None).expect("the defaults are valid")
+}
+
+// A client that looks transactions up in `fetcher`. Anything else it asks the RPC pool fails,
+// there's no network in tests.
+pub fn test_client(fetcher: Arc<MockFetcher>) -> Arc<SuiClient> {
+    let config = Arc::new(test_config());
+    let rpc = Arc::new(RpcPool::from_config(&config));
+    Arc::new(SuiClient::with_fetcher(config, rpc, fetcher))
+}
+
+pub fn digest(seed: u8) -> TransactionDigest {
+    TransactionDigest::new([seed; 32])
+}
+
+// "0x" and 64 hex digits, every byte `seed`
+pub fn address(seed: u8) -> String {
+    format!("0x{}", format!("{:02x}", seed).repeat(32))
+}
+
+pub fn response(value: Value) -> SuiTransactionBlockResponse {
+    serde_json::from_value(value).expect("fixture is a valid SuiTransactionBlockResponse")
+}
+
+// Successful effects (or failed ones with `error`) with the given gas costs, paid by `gas_owner`
+pub fn effects(
+    digest: TransactionDigest,
+    gas_owner: &str,
+    gas: (u64, u64, u64),
+    error: Option<&str>,
+) -> Value {
+    let (computation_cost, storage_cost, storage_rebate) = gas;
+    let status = match error {
+        Some(error) => json!({"status": "failure", "error": error}),
+        None => json!({"status": "success"}),
+    };
+    json!({
+        "messageVersion": "v1",
+        "status": status,
+        "executedEpoch": "500",
+        "gasUsed": {
+            "computationCost": computation_cost.to_string(),
+            "storageCost": storage_cost.to_string(),
+            "storageRebate": storage_rebate.to_string(),
+            "nonRefundableStorageFee": "0",
+        },
+        "transactionDigest": digest.to_string(),
+        "gasObject": {
+            "owner": {"AddressOwner": gas_owner},
+            "reference": {
+                "objectId": address(0xee),
+                "version": 2,
+                "digest": digest.to_string(),
+            },
+        },
+        "dependencies": [],
+    })
+}
+
+// An empty programmable transaction from `sender`, who also pays for gas
+pub fn transaction(sender: &str) -> Value {
+    json!({
+        "data": {
+            "messageVersion": "v1",
+            "transaction": {
+                "kind": "ProgrammableTransaction",
+                "inputs": [],
+                "transactions": [],
+            },
+            "sender": sender,
+            "gasData": {
+                "payment": [],
+                "owner": sender,
+                "price": "1000",
+                "budget": "10000000",
+            },
+        },
+        "txSignatures": [],
+    })
+}
+
+pub fn balance(owner: &str, coin_type: &str, amount: i128) -> Value {
+    json!({
+        "owner": {"AddressOwner": owner},
+        "coinType": coin_type,
+        "amount": amount.to_string(),
+    })
+}
+
+// A `created` object change, digest fixed since nothing reads it
+pub fn created_object(sender: &str, owner: &str, object_type: &str, object_id: &str) -> Value {
+    json!({
+        "type": "created",
+        "sender": sender,
+        "owner": {"AddressOwner": owner},
+        "objectType": object_type,
+        "objectId": object_id,
+        "version": "3",
+        "digest": digest(9).to_string(),
+    })
+}
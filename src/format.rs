@@ -0,0 +1,470 @@
+// Shared formatting for amounts and addresses shown to users
+
+use sui_types::object::Owner;
+
+use crate::error::ExplainError;
+
+// 1 SUI = 1,000,000,000 MIST
+pub const MIST_PER_SUI: f64 = 1_000_000_000.0;
+pub const SUI_DECIMALS: usize = 9;
+
+// Decimals shown when neither the request nor SUI_PRECISION says otherwise
+pub const DEFAULT_SUI_PRECISION: usize = 6;
+
+// How SUI amounts are rendered
+#[derive(Debug, Clone, Copy)]
+pub struct SuiStyle {
+    pub group: bool,      //Thousands separators
+    pub precision: usize, //Decimals, at most 9 (one MIST)
+    pub trim_zeros: bool, //"1.000000 SUI" -> "1 SUI"
+    pub mist: bool,       //Raw MIST integers instead, "1500000000"
+}
+
+// How addresses are shortened in sentences, from ADDRESS_FORMAT:
+//   short (default)  "0x1234...cdef", the first 6 and last 4 characters
+//   full             the whole address, for copying
+//   10...6           custom lengths, the first 10 and last 6 characters
+#[derive(Debug, Clone, Copy)]
+pub enum AddressFormat {
+    Full,
+    Short { prefix: usize, suffix: usize },
+}
+
+impl Default for AddressFormat {
+    fn default() -> Self {
+        AddressFormat::Short {
+            prefix: 6,
+            suffix: 4,
+        }
+    }
+}
+
+impl AddressFormat {
+    pub fn parse(format: &str) -> Option<Self> {
+        match format.trim() {
+            "full" => Some(AddressFormat::Full),
+            "short" => Some(Self::default()),
+            custom => {
+                let (prefix, suffix) = custom.split_once("...")?;
+                let (prefix, suffix) = (prefix.parse().ok()?, suffix.parse().ok()?);
+                // "0...0" would turn every address into a bare "..."
+                if prefix + suffix == 0 {
+                    return None;
+                }
+                Some(AddressFormat::Short { prefix, suffix })
+            }
+        }
+    }
+
+    //Addresses no longer than prefix + suffix are left whole, there's nothing to save
+    pub fn apply(&self, address: &str) -> String {
+        match *self {
+            AddressFormat::Short { prefix, suffix } if address.len() > prefix + suffix => {
+                match (address.get(..prefix), address.get(address.len() - suffix..)) {
+                    (Some(start), Some(end)) => format!("{}...{}", start, end),
+                    _ => address.to_string(),
+                }
+            }
+            _ => address.to_string(),
+        }
+    }
+}
+
+//MIST -> "1,234.500000 SUI". `signed` adds the +/- used for balance changes.
+//With style.mist the amount stays a plain MIST integer, "+1234500000000", for machine consumers.
+pub fn mist_to_sui_string(mist: i128, signed: bool, style: &SuiStyle) -> String {
+    if style.mist {
+        return if signed {
+            format!("{:+}", mist)
+        } else {
+            mist.to_string()
+        };
+    }
+
+    // Integer arithmetic throughout, f64 runs out of digits past about 9M SUI at 9 decimals.
+    // The dropped decimals round half up.
+    let precision = style.precision.min(SUI_DECIMALS);
+    let dropped = 10u128.pow((SUI_DECIMALS - precision) as u32);
+    let magnitude = mist.unsigned_abs();
+    let mut rounded = magnitude / dropped;
+    if magnitude % dropped >= dropped.div_ceil(2) {
+        rounded += 1;
+    }
+    let scale = 10u128.pow(precision as u32);
+
+    let sign = match (mist < 0, signed) {
+        (true, _) => "-",
+        (false, true) => "+",
+        (false, false) => "",
+    };
+    let mut number = format!("{}{}", sign, rounded / scale);
+    if precision > 0 {
+        number.push_str(&format!(".{:0width$}", rounded % scale, width = precision));
+    }
+
+    if style.trim_zeros && number.contains('.') {
+        number.truncate(number.trim_end_matches('0').trim_end_matches('.').len());
+    }
+    format!("{} SUI", maybe_group(number, style.group))
+}
+
+//Raw signed amount for coins we don't know the decimals of ("+1,500,000")
+pub fn raw_amount_string(amount: i128, group: bool) -> String {
+    maybe_group(format!("{:+}", amount), group)
+}
+
+//Owner of an object or balance in full, for the owner fields:
+//  "0x<64 hex>"                                  an account
+//  "object 0x<64 hex>"                           an object (dynamic fields, wrapped coins)
+//  "Shared (initial version 12)"                 shared, with the version needed to reference it
+//  "Immutable"
+//  "0x<64 hex> (consensus, since version 5)"     an account, sequenced through consensus
+pub fn format_owner(owner: &Owner) -> String {
+    format_owner_with(owner, |address| address.to_string())
+}
+
+//Same wording as format_owner, with each address written by `address` (e.g. shortened for sentences)
+pub fn format_owner_with(owner: &Owner, address: impl Fn(&str) -> String) -> String {
+    match owner {
+        Owner::AddressOwner(owner) => address(&owner.to_string()),
+        Owner::ObjectOwner(parent) => format!("object {}", address(&parent.to_string())),
+        Owner::Shared {
+            initial_shared_version,
+        } => format!(
+            "Shared (initial version {})",
+            initial_shared_version.value()
+        ),
+        Owner::Immutable => "Immutable".to_string(),
+        Owner::ConsensusAddressOwner {
+            start_version,
+            owner,
+        } => format!(
+            "{} (consensus, since version {})",
+            address(&owner.to_string()),
+            start_version.value()
+        ),
+    }
+}
+
+//Signed amount of a coin with `decimals` places, trailing zeros dropped: 1500250000 at 6 -> "+1,500.25"
+pub fn scaled_amount_string(amount: i128, decimals: u8, group: bool) -> String {
+    // u128 holds 10^38 at most, no real coin comes close
+    let decimals = decimals.min(38) as usize;
+    let scale = 10u128.pow(decimals as u32);
+    let magnitude = amount.unsigned_abs();
+
+    let sign = if amount < 0 { "-" } else { "+" };
+    let mut number = format!("{}{}", sign, magnitude / scale);
+    let fraction = magnitude % scale;
+    if fraction > 0 {
+        let digits = format!("{:0width$}", fraction, width = decimals);
+        number.push('.');
+        number.push_str(digits.trim_end_matches('0'));
+    }
+    maybe_group(number, group)
+}
+
+//"1.5", "1.5 SUI", "1,500,000,000 MIST" -> 1500000000. The inverse of mist_to_sui_string, for
+//amounts users type into requests. Negative, fractional MIST and sub-MIST SUI amounts are rejected.
+pub fn parse_sui_amount(input: &str) -> Result<u64, ExplainError> {
+    let invalid = || ExplainError::InvalidAmount(input.to_string());
+
+    let trimmed = input.trim();
+    let upper = trimmed.to_ascii_uppercase();
+    let (number, is_mist) = if upper.ends_with("MIST") {
+        (&trimmed[..trimmed.len() - 4], true)
+    } else if upper.ends_with("SUI") {
+        (&trimmed[..trimmed.len() - 3], false)
+    } else {
+        (trimmed, false)
+    };
+    let number = number.trim().replace(',', "");
+
+    let (whole, fraction) = match number.split_once('.') {
+        Some((whole, fraction)) if !is_mist => (whole, fraction),
+        Some(_) => return Err(invalid()),
+        None => (number.as_str(), ""),
+    };
+    let all_digits = |s: &str| s.chars().all(|c| c.is_ascii_digit());
+    if whole.is_empty() && fraction.is_empty() || !all_digits(whole) || !all_digits(fraction) {
+        return Err(invalid());
+    }
+
+    let whole: u64 = if whole.is_empty() {
+        0
+    } else {
+        whole.parse().map_err(|_| invalid())?
+    };
+    if is_mist {
+        return Ok(whole);
+    }
+
+    // The 9th decimal is one MIST, anything finer can't be represented
+    if fraction.len() > SUI_DECIMALS {
+        return Err(invalid());
+    }
+    let fraction: u64 = format!("{:0<width$}", fraction, width = SUI_DECIMALS)
+        .parse()
+        .map_err(|_| invalid())?;
+
+    whole
+        .checked_mul(MIST_PER_SUI as u64)
+        .and_then(|mist| mist.checked_add(fraction))
+        .ok_or_else(invalid)
+}
+
+//Comma separators in the whole-number part only: "+1500000.25" -> "+1,500,000.25"
+pub fn group_thousands(number: &str) -> String {
+    let sign_len = if number.starts_with(['+', '-']) { 1 } else { 0 };
+    let (sign, unsigned) = number.split_at(sign_len);
+    let (whole, fraction) = match unsigned.find('.') {
+        Some(dot) => unsigned.split_at(dot),
+        None => (unsigned, ""),
+    };
+
+    let mut grouped = String::with_capacity(whole.len() + whole.len() / 3);
+    for (i, digit) in whole.chars().enumerate() {
+        if i > 0 && (whole.len() - i) % 3 == 0 {
+            grouped.push(',');
+        }
+        grouped.push(digit);
+    }
+
+    format!("{}{}{}", sign, grouped, fraction)
+}
+
+fn maybe_group(number: String, group: bool) -> String {
+    if group {
+        group_thousands(&number)
+    } else {
+        number
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use sui_types::base_types::{SequenceNumber, SuiAddress};
+
+    #[test]
+    fn groups_thousands_across_magnitudes() {
+        assert_eq!(group_thousands("0"), "0");
+        assert_eq!(group_thousands("999"), "999");
+        assert_eq!(group_thousands("1000"), "1,000");
+        assert_eq!(group_thousands("123456"), "123,456");
+        assert_eq!(group_thousands("1234567890"), "1,234,567,890");
+    }
+
+    #[test]
+    fn groups_thousands_after_the_sign_and_before_the_decimals() {
+        assert_eq!(group_thousands("-1000"), "-1,000");
+        assert_eq!(group_thousands("+999"), "+999");
+        assert_eq!(group_thousands("+1500000.25"), "+1,500,000.25");
+        assert_eq!(group_thousands("1234.56789"), "1,234.56789");
+        assert_eq!(group_thousands("0.000001"), "0.000001");
+    }
+
+    fn style(group: bool, precision: usize, trim_zeros: bool) -> SuiStyle {
+        SuiStyle {
+            group,
+            precision,
+            trim_zeros,
+            mist: false,
+        }
+    }
+
+    #[test]
+    fn renders_sui_at_each_precision() {
+        assert_eq!(
+            mist_to_sui_string(1_700_000_000, false, &style(false, 0, false)),
+            "2 SUI"
+        );
+        assert_eq!(
+            mist_to_sui_string(1_500_000_000, false, &style(false, 6, false)),
+            "1.500000 SUI"
+        );
+        assert_eq!(
+            mist_to_sui_string(1_000_000_001, false, &style(false, 9, false)),
+            "1.000000001 SUI"
+        );
+        // Past one MIST there's nothing left to show
+        assert_eq!(
+            mist_to_sui_string(1_000_000_001, false, &style(false, 12, false)),
+            "1.000000001 SUI"
+        );
+        assert_eq!(
+            mist_to_sui_string(-2_500_000, true, &style(false, 6, false)),
+            "-0.002500 SUI"
+        );
+        assert_eq!(
+            mist_to_sui_string(1_999_999_500, false, &style(false, 6, false)),
+            "2.000000 SUI"
+        );
+    }
+
+    #[test]
+    fn large_amounts_keep_every_digit() {
+        // 12,345,678,901.234567891 SUI, far past what an f64 holds to the MIST
+        assert_eq!(
+            mist_to_sui_string(12_345_678_901_234_567_891, true, &style(true, 9, false)),
+            "+12,345,678,901.234567891 SUI"
+        );
+        assert_eq!(
+            mist_to_sui_string(-i128::MAX, false, &style(false, 9, false)),
+            format!(
+                "-{}.{} SUI",
+                i128::MAX / 1_000_000_000,
+                i128::MAX % 1_000_000_000
+            )
+        );
+    }
+
+    #[test]
+    fn trims_only_the_decimals() {
+        assert_eq!(
+            mist_to_sui_string(1_000_000_000, false, &style(false, 6, true)),
+            "1 SUI"
+        );
+        assert_eq!(
+            mist_to_sui_string(1_500_000_000, true, &style(false, 6, true)),
+            "+1.5 SUI"
+        );
+        // No decimal point, the zeros are part of the number
+        assert_eq!(
+            mist_to_sui_string(20_000_000_000, false, &style(false, 0, true)),
+            "20 SUI"
+        );
+    }
+
+    #[test]
+    fn groups_and_trims_together() {
+        assert_eq!(
+            mist_to_sui_string(1_234_500_000_000, true, &style(true, 6, true)),
+            "+1,234.5 SUI"
+        );
+        assert_eq!(
+            mist_to_sui_string(1_000_000_000_000, false, &style(true, 6, true)),
+            "1,000 SUI"
+        );
+        assert_eq!(
+            mist_to_sui_string(1_234_500_000_000, false, &style(true, 6, false)),
+            "1,234.500000 SUI"
+        );
+    }
+
+    #[test]
+    fn mist_style_keeps_the_raw_integer() {
+        let mist = SuiStyle {
+            mist: true,
+            ..style(true, 6, true)
+        };
+        assert_eq!(
+            mist_to_sui_string(1_500_000_000, true, &mist),
+            "+1500000000"
+        );
+        assert_eq!(
+            mist_to_sui_string(1_500_000_000, false, &mist),
+            "1500000000"
+        );
+    }
+
+    const ADDRESS: &str = "0x1234567890abcdef1234567890abcdef1234567890abcdef1234567890abcdef";
+
+    #[test]
+    fn shortens_addresses_by_format() {
+        assert_eq!(AddressFormat::default().apply(ADDRESS), "0x1234...cdef");
+        assert_eq!(
+            AddressFormat::parse("10...6").unwrap().apply(ADDRESS),
+            "0x12345678...abcdef"
+        );
+        assert_eq!(
+            AddressFormat::parse("full").unwrap().apply(ADDRESS),
+            ADDRESS
+        );
+    }
+
+    #[test]
+    fn leaves_addresses_no_longer_than_the_shortened_form() {
+        let format = AddressFormat::Short {
+            prefix: 6,
+            suffix: 4,
+        };
+        assert_eq!(format.apply("0x12345678"), "0x12345678");
+        assert_eq!(format.apply("0x123456789"), "0x1234...6789");
+    }
+
+    #[test]
+    fn rejects_unusable_address_formats() {
+        assert!(AddressFormat::parse("0...0").is_none());
+        assert!(AddressFormat::parse("short-ish").is_none());
+        assert!(AddressFormat::parse("6...").is_none());
+        assert!(AddressFormat::parse(" short ").is_some());
+    }
+
+    #[test]
+    fn formats_every_owner_kind() {
+        let account: SuiAddress = ADDRESS.parse().unwrap();
+        let version = SequenceNumber::from_u64(12);
+
+        assert_eq!(format_owner(&Owner::AddressOwner(account)), ADDRESS);
+        assert_eq!(
+            format_owner(&Owner::ObjectOwner(account)),
+            format!("object {}", ADDRESS)
+        );
+        assert_eq!(
+            format_owner(&Owner::Shared {
+                initial_shared_version: version
+            }),
+            "Shared (initial version 12)"
+        );
+        assert_eq!(format_owner(&Owner::Immutable), "Immutable");
+        assert_eq!(
+            format_owner(&Owner::ConsensusAddressOwner {
+                start_version: version,
+                owner: account,
+            }),
+            format!("{} (consensus, since version 12)", ADDRESS)
+        );
+    }
+
+    #[test]
+    fn parses_sui_amounts() {
+        assert_eq!(parse_sui_amount("1.5").unwrap(), 1_500_000_000);
+        assert_eq!(parse_sui_amount("1.5 SUI").unwrap(), 1_500_000_000);
+        assert_eq!(parse_sui_amount(" 2 sui ").unwrap(), 2_000_000_000);
+        assert_eq!(parse_sui_amount(".25").unwrap(), 250_000_000);
+        assert_eq!(parse_sui_amount("0.000000001").unwrap(), 1);
+        assert_eq!(parse_sui_amount("1,500 MIST").unwrap(), 1_500);
+        assert_eq!(parse_sui_amount("1,000.5 SUI").unwrap(), 1_000_500_000_000);
+    }
+
+    #[test]
+    fn rejects_malformed_sui_amounts() {
+        for input in [
+            "",
+            "SUI",
+            ".",
+            "-1",
+            "+1",
+            "1.5.5",
+            "abc",
+            "1.5 MIST",
+            "1e9",
+            "0.0000000001",
+        ] {
+            let error = parse_sui_amount(input).unwrap_err();
+            assert_eq!(error.code(), "INVALID_AMOUNT", "{:?}", input);
+        }
+    }
+
+    #[test]
+    fn rejects_sui_amounts_past_u64() {
+        assert_eq!(
+            parse_sui_amount("18446744073709551615 MIST").unwrap(),
+            u64::MAX
+        );
+        assert!(parse_sui_amount("18446744073709551616 MIST").is_err());
+        // Fits as a whole number, but not once converted to MIST
+        assert!(parse_sui_amount("18446744074 SUI").is_err());
+    }
+}
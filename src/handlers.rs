@@ -1,60 +1,939 @@
-use crate::models::{ExplainRequest, ExplainResponse};
+use crate::decode::{decode_transaction_bytes, describe_transaction_data};
+use crate::diff::diff_explanations;
+use crate::error::ExplainError;
+use crate::format::parse_sui_amount;
+use crate::models::{
+    AnomalyBatchResponse, BatchExplainItem, BatchExplainRequest, BatchSummary, CallsParams,
+    CallsResponse, DecodeRequest, DecodeResponse, DiffRequest, DiffResponse, DryRunRequest,
+    DryRunResponse, ExplainOptions, ExplainRequest, ExplainResponse, FormatParams, HistoryParams,
+    HistoryResponse, PnlRequest, PnlResponse, StatsRequest, StatsResponse, StatusResponse,
+};
+use crate::request_id::{self, RequestId};
 use crate::sui_client::SuiClient;
-use axum::{Json, http::StatusCode};
+use axum::{
+    Extension, Json,
+    body::Body,
+    extract::{Path, Query, State},
+    http::{HeaderMap, Method, StatusCode, Uri, header},
+    response::{IntoResponse, Response},
+};
+use futures::StreamExt;
+use serde::Serialize;
+use std::{
+    collections::HashSet,
+    convert::Infallible,
+    sync::{Arc, OnceLock},
+};
+
+// only_anomalies treats anything above 0.1 SUI of gas as unusual unless high_gas or high_gas_mist
+// says otherwise
+const DEFAULT_HIGH_GAS_MIST: u64 = 100_000_000;
+
+const NDJSON: &str = "application/x-ndjson";
+const MSGPACK: &str = "application/msgpack";
+
+// DEFAULT_PRETTY, set once at startup. Rejections are rendered outside any handler state, so it
+// lives here rather than on the client.
+static DEFAULT_PRETTY: OnceLock<bool> = OnceLock::new();
+
+pub fn set_default_pretty(pretty: bool) {
+    let _ = DEFAULT_PRETTY.set(pretty);
+}
 
 // Handle POST /api/explain requests
 
 //This function receives a transaction digest from the user, uses SuiClient to fetch and explain it and returns the explanation as JSON.
+//Clients that send Accept: application/msgpack get the same response encoded as MessagePack instead.
 
 pub async fn explain_transaction(
+    State(client): State<Arc<SuiClient>>,
+    Extension(RequestId(request_id)): Extension<RequestId>,
+    headers: HeaderMap,
+    Query(format): Query<FormatParams>,
     Json(payload): Json<ExplainRequest>, // Automatically parse JSON body
-) -> (StatusCode, Json<ExplainResponse>) {
-    println!("Explaining transaction: {}", payload.digest);
-
-    // Create a new Sui client
-    let client = match SuiClient::new().await {
-        Ok(c) => c,
-        Err(e) => {
-            eprintln!("Failed to create Sui client: {}", e);
-            return (
-                StatusCode::INTERNAL_SERVER_ERROR,
-                Json(ExplainResponse {
-                    success: false,
-                    explanation: None,
-                    error: Some(format!("Failed to connect to Sui: {}", e)),
-                }),
+) -> Response {
+    println!(
+        "[{}] Explaining transaction: {}",
+        request_id, payload.digest
+    );
+
+    // Fetch and explain the transaction
+    let (status, mut response) =
+        explain_with(&client, &payload.digest, &payload.options, &request_id).await;
+    response.request_id = Some(request_id);
+    if accepts(&headers, MSGPACK) {
+        return msgpack_response(status, &response);
+    }
+    json_response(status, &response, format.pretty)
+}
+
+// Handle POST /api/explain/batch requests
+
+//Explains the digests concurrently, BATCH_CONCURRENCY at a time. Responds with a JSON array in
+//request order, or streams one JSON object per line as each explanation completes when the client
+//accepts application/x-ndjson. Repeated digests are explained once. With only_anomalies, routine
+//successes are left out: the array becomes {items, total, omitted} and the stream ends with a
+//{total, omitted} line.
+
+pub async fn explain_batch(
+    State(client): State<Arc<SuiClient>>,
+    Extension(RequestId(request_id)): Extension<RequestId>,
+    headers: HeaderMap,
+    Query(format): Query<FormatParams>,
+    Json(payload): Json<BatchExplainRequest>,
+) -> Response {
+    println!(
+        "[{}] Explaining batch of {} transactions",
+        request_id,
+        payload.digests.len()
+    );
+
+    let digests = match validate_batch(payload.digests, client.config().batch_max_size) {
+        Ok(digests) => digests,
+        Err(e) => {
+            eprintln!("[{}] Rejected batch: {}", request_id, e);
+            return rejection(e, Some(request_id), format.pretty);
+        }
+    };
+    let total = digests.len();
+    let high_gas_mist = match payload.high_gas.as_deref().map(parse_sui_amount) {
+        Some(Ok(mist)) => mist,
+        Some(Err(e)) => {
+            eprintln!("[{}] Rejected batch: {}", request_id, e);
+            return rejection(e, Some(request_id), format.pretty);
+        }
+        None => payload.high_gas_mist.unwrap_or(DEFAULT_HIGH_GAS_MIST),
+    };
+    let filter = payload.only_anomalies.then(|| AnomalyFilter {
+        high_gas_mist,
+        categories: payload
+            .categories
+            .iter()
+            .map(|category| category.to_lowercase())
+            .collect(),
+    });
+
+    // At most BATCH_CONCURRENCY explanations run at once. The stream is only polled as fast as the
+    // response is written, so a slow NDJSON reader holds back new fetches instead of piling up results.
+    let options = payload.options;
+    let concurrency = client.config().batch_concurrency;
+    let stream_id = request_id.clone();
+    let results = futures::stream::iter(digests.into_iter().enumerate())
+        .map(move |(index, digest)| {
+            let client = client.clone();
+            let options = options.clone();
+            let request_id = request_id.clone();
+            async move {
+                let (_, mut response) = explain_with(&client, &digest, &options, &request_id).await;
+                response.request_id = Some(request_id);
+                (index, BatchExplainItem { digest, response })
+            }
+        })
+        .buffer_unordered(concurrency);
+
+    if accepts(&headers, NDJSON) {
+        // The body is written after this handler returns, outside the request's logging scope
+        let state = (Box::pin(results), filter, 0, stream_id);
+        let stream = futures::stream::unfold(state, move |state| async move {
+            let (mut results, filter, mut omitted, id) = state;
+            loop {
+                match request_id::scope(id.clone(), results.next()).await {
+                    Some((_, item)) if filter.as_ref().is_some_and(|f| !f.keeps(&item)) => {
+                        omitted += 1;
+                    }
+                    Some((_, item)) => {
+                        return Some((
+                            Ok::<_, Infallible>(ndjson_line(&item)),
+                            (results, filter, omitted, id),
+                        ));
+                    }
+                    // Unfiltered streams just end, filtered ones finish with what they left out
+                    None => {
+                        if filter.is_none() {
+                            return None;
+                        }
+                        let line = ndjson_line(&BatchSummary { total, omitted });
+                        return Some((Ok(line), (results, None, omitted, id)));
+                    }
+                }
+            }
+        });
+
+        return ([(header::CONTENT_TYPE, NDJSON)], Body::from_stream(stream)).into_response();
+    }
+
+    let mut items: Vec<(usize, BatchExplainItem)> = results.collect().await;
+    items.sort_by_key(|(index, _)| *index);
+
+    let items: Vec<BatchExplainItem> = items.into_iter().map(|(_, item)| item).collect();
+    match filter {
+        Some(filter) => {
+            let items: Vec<BatchExplainItem> = items
+                .into_iter()
+                .filter(|item| filter.keeps(item))
+                .collect();
+            let response = AnomalyBatchResponse {
+                summary: BatchSummary {
+                    total,
+                    omitted: total - items.len(),
+                },
+                items,
+            };
+            json_response(StatusCode::OK, &response, format.pretty)
+        }
+        None => json_response(StatusCode::OK, &items, format.pretty),
+    }
+}
+
+// What only_anomalies keeps: failures, errors, high gas, watchlist hits and the asked-for categories
+struct AnomalyFilter {
+    high_gas_mist: u64,
+    categories: Vec<String>, //Lowercased
+}
+
+impl AnomalyFilter {
+    fn keeps(&self, item: &BatchExplainItem) -> bool {
+        // Couldn't explain it at all, that's worth a look too
+        let Some(explanation) = &item.response.explanation else {
+            return true;
+        };
+
+        explanation.status != "Success"
+            || explanation.gas_used > self.high_gas_mist
+            || !explanation.matched_addresses.is_empty()
+            || explanation
+                .category
+                .as_ref()
+                .is_some_and(|category| self.categories.contains(&category.to_lowercase()))
+    }
+}
+
+// Handle POST /api/diff requests
+
+//Explains both transactions and returns what differs between them (status, gas, object and balance changes).
+
+pub async fn diff_transactions(
+    State(client): State<Arc<SuiClient>>,
+    Extension(RequestId(request_id)): Extension<RequestId>,
+    Query(format): Query<FormatParams>,
+    Json(payload): Json<DiffRequest>,
+) -> Response {
+    println!(
+        "[{}] Diffing transactions {} and {}",
+        request_id, payload.left, payload.right
+    );
+
+    let (left, right) = tokio::join!(
+        client.explain_transaction(&payload.left, &payload.options),
+        client.explain_transaction(&payload.right, &payload.options),
+    );
+
+    let (left, right) = match (left, right) {
+        (Ok((left, _)), Ok((right, _))) => (left, right),
+        (Err(e), _) | (_, Err(e)) => {
+            eprintln!("[{}] Failed to diff transactions: {}", request_id, e);
+            let response = DiffResponse {
+                success: false,
+                diff: None,
+                error: Some(e.to_string()),
+                error_code: Some(e.code().to_string()),
+                request_id: Some(request_id),
+            };
+            return json_response(e.status(), &response, format.pretty);
+        }
+    };
+
+    let response = DiffResponse {
+        success: true,
+        diff: Some(diff_explanations(&left, &right)),
+        error: None,
+        error_code: None,
+        request_id: Some(request_id),
+    };
+    json_response(StatusCode::OK, &response, format.pretty)
+}
+
+// Handle POST /api/pnl requests
+
+//Explains every digest and adds up what one address gained or lost per coin, an accounting view.
+//Takes the same limits as a batch. Without digests, from_ms and to_ms pick the transactions instead.
+
+pub async fn profit_and_loss(
+    State(client): State<Arc<SuiClient>>,
+    Extension(RequestId(request_id)): Extension<RequestId>,
+    Query(format): Query<FormatParams>,
+    Json(payload): Json<PnlRequest>,
+) -> Response {
+    let max_size = client.config().batch_max_size;
+    let in_range =
+        payload.digests.is_empty() && (payload.from_ms.is_some() || payload.to_ms.is_some());
+    let digests = if in_range {
+        println!(
+            "[{}] Computing net balances of {} over a time range",
+            request_id, payload.address
+        );
+        client
+            .address_digests_in_range(&payload.address, payload.from_ms, payload.to_ms, max_size)
+            .await
+    } else {
+        println!(
+            "[{}] Computing net balances of {} over {} transactions",
+            request_id,
+            payload.address,
+            payload.digests.len()
+        );
+        validate_batch(payload.digests, max_size)
+    };
+
+    let result = match digests {
+        Ok(digests) => client
+            .net_balances(&payload.address, &digests, &payload.options)
+            .await
+            .map(|net| (net, digests.len())),
+        Err(e) => Err(e),
+    };
+
+    match result {
+        Ok((net, transaction_count)) => {
+            let response = PnlResponse {
+                success: true,
+                address: payload.address,
+                net,
+                transaction_count,
+                error: None,
+                error_code: None,
+                request_id: Some(request_id),
+            };
+            json_response(StatusCode::OK, &response, format.pretty)
+        }
+        Err(e) => {
+            eprintln!("[{}] Failed to compute net balances: {}", request_id, e);
+            let response = PnlResponse {
+                success: false,
+                address: payload.address,
+                net: vec![],
+                transaction_count: 0,
+                error: Some(e.to_string()),
+                error_code: Some(e.code().to_string()),
+                request_id: Some(request_id),
+            };
+            json_response(e.status(), &response, format.pretty)
+        }
+    }
+}
+
+// Handle GET /api/status/:digest requests
+
+//Only reports whether the transaction succeeded and what gas it used, the cheapest query we offer.
+
+pub async fn transaction_status(
+    State(client): State<Arc<SuiClient>>,
+    Extension(RequestId(request_id)): Extension<RequestId>,
+    Path(digest): Path<String>,
+    Query(format): Query<FormatParams>,
+) -> Response {
+    println!(
+        "[{}] Checking status of transaction: {}",
+        request_id, digest
+    );
+
+    match client
+        .transaction_status(&digest, &ExplainOptions::default())
+        .await
+    {
+        Ok(status) => {
+            let response = StatusResponse {
+                success: true,
+                status: Some(status),
+                error: None,
+                error_code: None,
+                request_id: Some(request_id),
+            };
+            json_response(StatusCode::OK, &response, format.pretty)
+        }
+        Err(e) => {
+            eprintln!("[{}] Failed to check transaction status: {}", request_id, e);
+            let response = StatusResponse {
+                success: false,
+                status: None,
+                error: Some(e.to_string()),
+                error_code: Some(e.code().to_string()),
+                request_id: Some(request_id),
+            };
+            json_response(e.status(), &response, format.pretty)
+        }
+    }
+}
+
+// Handle GET /api/calls/:digest requests
+
+//Returns just the decoded Move calls, for developers who don't care about object and balance changes.
+//?function=module::function narrows them to one function and decodes its arguments.
+//?command_offset= and ?command_limit= page through them before the function filter, total says how
+//many there are in the transaction.
+
+pub async fn transaction_calls(
+    State(client): State<Arc<SuiClient>>,
+    Extension(RequestId(request_id)): Extension<RequestId>,
+    Path(digest): Path<String>,
+    Query(format): Query<FormatParams>,
+    Query(params): Query<CallsParams>,
+) -> Response {
+    println!(
+        "[{}] Decoding Move calls of transaction: {}",
+        request_id, digest
+    );
+
+    let calls = client
+        .move_calls(
+            &digest,
+            params.function.as_deref(),
+            params.command_offset.unwrap_or(0),
+            params.command_limit.unwrap_or(usize::MAX),
+        )
+        .await;
+    match calls {
+        Ok((calls, total)) => {
+            let response = CallsResponse {
+                success: true,
+                digest,
+                calls,
+                total,
+                error: None,
+                error_code: None,
+                request_id: Some(request_id),
+            };
+            json_response(StatusCode::OK, &response, format.pretty)
+        }
+        Err(e) => {
+            eprintln!("[{}] Failed to decode Move calls: {}", request_id, e);
+            let response = CallsResponse {
+                success: false,
+                digest,
+                calls: vec![],
+                total: 0,
+                error: Some(e.to_string()),
+                error_code: Some(e.code().to_string()),
+                request_id: Some(request_id),
+            };
+            json_response(e.status(), &response, format.pretty)
+        }
+    }
+}
+
+// Handle GET /api/object/:id/last-tx requests
+
+//Starts from an object instead of a transaction: finds the transaction that last touched it and explains that.
+
+pub async fn object_last_tx(
+    State(client): State<Arc<SuiClient>>,
+    Extension(RequestId(request_id)): Extension<RequestId>,
+    Path(object_id): Path<String>,
+    Query(format): Query<FormatParams>,
+) -> Response {
+    println!(
+        "[{}] Explaining last transaction of object: {}",
+        request_id, object_id
+    );
+
+    let (status, mut response) = match client.last_transaction_for_object(&object_id).await {
+        Ok(digest) => explain_with(&client, &digest, &ExplainOptions::default(), &request_id).await,
+        Err(e) => {
+            eprintln!(
+                "[{}] Failed to find last transaction of object: {}",
+                request_id, e
             );
+            error_response(e)
         }
     };
+    response.request_id = Some(request_id);
+    json_response(status, &response, format.pretty)
+}
 
-    // Fetch and explain the transaction
-    match client.explain_transaction(&payload.digest).await {
-        Ok(explanation) => {
-            println!("Successfully explained transaction");
+// Handle GET /api/address/:address/history requests
+
+//Lists what an address sent, newest first, with one summary per transaction. from_ms and to_ms
+//narrow it to a period, e.g. a tax year.
+
+pub async fn address_history(
+    State(client): State<Arc<SuiClient>>,
+    Extension(RequestId(request_id)): Extension<RequestId>,
+    Path(address): Path<String>,
+    Query(format): Query<FormatParams>,
+    Query(params): Query<HistoryParams>,
+) -> Response {
+    println!(
+        "[{}] Listing transactions of address: {}",
+        request_id, address
+    );
+
+    match client
+        .address_history(&address, params.limit, params.from_ms, params.to_ms)
+        .await
+    {
+        Ok(transactions) => {
+            let response = HistoryResponse {
+                success: true,
+                address,
+                transactions,
+                error: None,
+                error_code: None,
+                request_id: Some(request_id),
+            };
+            json_response(StatusCode::OK, &response, format.pretty)
+        }
+        Err(e) => {
+            eprintln!("[{}] Failed to list address history: {}", request_id, e);
+            let response = HistoryResponse {
+                success: false,
+                address,
+                transactions: vec![],
+                error: Some(e.to_string()),
+                error_code: Some(e.code().to_string()),
+                request_id: Some(request_id),
+            };
+            json_response(e.status(), &response, format.pretty)
+        }
+    }
+}
+
+// Handle POST /api/diff/dry-run requests
+
+//Compares what the transaction did with what a dry run of the same bytes predicts, as a regular diff.
+
+pub async fn diff_dry_run(
+    State(client): State<Arc<SuiClient>>,
+    Extension(RequestId(request_id)): Extension<RequestId>,
+    Query(format): Query<FormatParams>,
+    Json(payload): Json<ExplainRequest>,
+) -> Response {
+    println!(
+        "[{}] Diffing transaction {} against its dry run",
+        request_id, payload.digest
+    );
+
+    let response = match client
+        .diff_against_dry_run(&payload.digest, &payload.options)
+        .await
+    {
+        Ok(diff) => DiffResponse {
+            success: true,
+            diff: Some(diff),
+            error: None,
+            error_code: None,
+            request_id: Some(request_id),
+        },
+        Err(e) => {
+            eprintln!("[{}] Failed to diff against dry run: {}", request_id, e);
+            let response = DiffResponse {
+                success: false,
+                diff: None,
+                error: Some(e.to_string()),
+                error_code: Some(e.code().to_string()),
+                request_id: Some(request_id),
+            };
+            return json_response(e.status(), &response, format.pretty);
+        }
+    };
+    json_response(StatusCode::OK, &response, format.pretty)
+}
+
+// Handle POST /api/stats requests
+
+//Aggregates a set of transactions: gas spent, successes and failures, categories and value moved per coin.
+
+pub async fn transaction_stats(
+    State(client): State<Arc<SuiClient>>,
+    Extension(RequestId(request_id)): Extension<RequestId>,
+    Query(format): Query<FormatParams>,
+    Json(payload): Json<StatsRequest>,
+) -> Response {
+    println!(
+        "[{}] Computing stats over {} transactions",
+        request_id,
+        payload.digests.len()
+    );
+
+    match validate_batch(payload.digests, client.config().batch_max_size) {
+        Ok(digests) => {
+            let stats = client.batch_stats(&digests, &payload.options).await;
+            let response = StatsResponse {
+                success: true,
+                stats: Some(stats),
+                error: None,
+                error_code: None,
+                request_id: Some(request_id),
+            };
+            json_response(StatusCode::OK, &response, format.pretty)
+        }
+        Err(e) => {
+            eprintln!("[{}] Rejected stats request: {}", request_id, e);
+            let response = StatsResponse {
+                success: false,
+                stats: None,
+                error: Some(e.to_string()),
+                error_code: Some(e.code().to_string()),
+                request_id: Some(request_id),
+            };
+            json_response(e.status(), &response, format.pretty)
+        }
+    }
+}
+
+// Handle POST /api/decode requests
+
+//Decodes base64 transaction bytes for previewing a transaction before it's sent. Only touches the network
+//when decode_args asks for the called functions' signatures.
+
+pub async fn decode_transaction(
+    State(client): State<Arc<SuiClient>>,
+    Extension(RequestId(request_id)): Extension<RequestId>,
+    Query(format): Query<FormatParams>,
+    Json(payload): Json<DecodeRequest>,
+) -> Response {
+    println!("[{}] Decoding transaction bytes", request_id);
+
+    match decode_transaction_bytes(&payload.tx_bytes) {
+        Ok(tx_data) => {
+            let mut decoded = describe_transaction_data(&tx_data);
+            if payload.decode_args {
+                client
+                    .decode_call_args(&tx_data, &mut decoded.calls, 0, None)
+                    .await;
+            }
+            let response = DecodeResponse {
+                success: true,
+                decoded: Some(decoded),
+                error: None,
+                error_code: None,
+                request_id: Some(request_id),
+            };
+            json_response(StatusCode::OK, &response, format.pretty)
+        }
+        Err(e) => {
+            eprintln!("[{}] Failed to decode transaction: {}", request_id, e);
+            let response = DecodeResponse {
+                success: false,
+                decoded: None,
+                error: Some(e.to_string()),
+                error_code: Some(e.code().to_string()),
+                request_id: Some(request_id),
+            };
+            json_response(e.status(), &response, format.pretty)
+        }
+    }
+}
+
+// Handle POST /api/dry-run requests
+
+//Runs base64 transaction bytes against the current chain state without executing them, to preview
+//effects and gas before signing. Gas is reported even when the transaction would fail.
+
+pub async fn dry_run_transaction(
+    State(client): State<Arc<SuiClient>>,
+    Extension(RequestId(request_id)): Extension<RequestId>,
+    Query(format): Query<FormatParams>,
+    Json(payload): Json<DryRunRequest>,
+) -> Response {
+    println!("[{}] Dry-running transaction bytes", request_id);
+
+    let result = match decode_transaction_bytes(&payload.tx_bytes) {
+        Ok(tx_data) => client.dry_run(tx_data, &payload.options).await,
+        Err(e) => Err(e),
+    };
+
+    match result {
+        Ok((explanation, gas)) => {
+            let response = DryRunResponse {
+                success: true,
+                explanation: Some(explanation),
+                gas: Some(gas),
+                error: None,
+                error_code: None,
+                request_id: Some(request_id),
+            };
+            json_response(StatusCode::OK, &response, format.pretty)
+        }
+        Err(e) => {
+            eprintln!("[{}] Failed to dry-run transaction: {}", request_id, e);
+            let response = DryRunResponse {
+                success: false,
+                explanation: None,
+                gas: None,
+                error: Some(e.to_string()),
+                error_code: Some(e.code().to_string()),
+                request_id: Some(request_id),
+            };
+            json_response(e.status(), &response, format.pretty)
+        }
+    }
+}
+
+// Unknown /api/... paths get a JSON 404 in the usual error shape instead of the static file service's
+pub async fn route_not_found(
+    Extension(RequestId(request_id)): Extension<RequestId>,
+    uri: Uri,
+    Query(format): Query<FormatParams>,
+) -> Response {
+    let e = ExplainError::RouteNotFound(uri.path().to_string());
+    rejection(e, Some(request_id), format.pretty)
+}
+
+// Known route, wrong method (e.g. GET /api/explain)
+pub async fn method_not_allowed(
+    Extension(RequestId(request_id)): Extension<RequestId>,
+    method: Method,
+    uri: Uri,
+    Query(format): Query<FormatParams>,
+) -> Response {
+    let e = ExplainError::MethodNotAllowed(method.to_string(), uri.path().to_string());
+    rejection(e, Some(request_id), format.pretty)
+}
+
+/// Health check endpoint - just returns OK
+pub async fn health_check() -> &'static str {
+    "OK"
+}
+
+// Explain a single digest with an existing client and map the outcome to a status code
+async fn explain_with(
+    client: &SuiClient,
+    digest: &str,
+    options: &ExplainOptions,
+    request_id: &str,
+) -> (StatusCode, ExplainResponse) {
+    match client.explain_transaction(digest, options).await {
+        Ok((explanation, meta)) => {
+            println!("[{}] Successfully explained transaction", request_id);
             (
                 StatusCode::OK,
-                Json(ExplainResponse {
+                ExplainResponse {
                     success: true,
                     explanation: Some(explanation),
                     error: None,
-                }),
+                    error_code: None,
+                    meta: Some(meta),
+                    request_id: None,
+                },
             )
         }
         Err(e) => {
-            eprintln!("Failed to explain transaction: {}", e);
-            (
-                StatusCode::BAD_REQUEST,
-                Json(ExplainResponse {
-                    success: false,
-                    explanation: None,
-                    error: Some(e.to_string()),
-                }),
-            )
+            eprintln!("[{}] Failed to explain transaction: {}", request_id, e);
+            error_response(e)
         }
     }
 }
 
-/// Health check endpoint - just returns OK
-pub async fn health_check() -> &'static str {
-    "OK"
+fn error_response(e: ExplainError) -> (StatusCode, ExplainResponse) {
+    (
+        e.status(),
+        ExplainResponse {
+            success: false,
+            explanation: None,
+            error: Some(e.to_string()),
+            error_code: Some(e.code().to_string()),
+            meta: None,
+            request_id: None,
+        },
+    )
+}
+
+//The usual error body, for turning a request away before it gets to the work, e.g. from middleware
+pub fn rejection(e: ExplainError, request_id: Option<String>, pretty: Option<bool>) -> Response {
+    let (status, mut response) = error_response(e);
+    response.request_id = request_id;
+    json_response(status, &response, pretty)
+}
+
+// Drop repeated digests (keeping the first one's position) and enforce the size limit
+fn validate_batch(digests: Vec<String>, max_size: usize) -> Result<Vec<String>, ExplainError> {
+    if digests.is_empty() {
+        return Err(ExplainError::InvalidBatch(
+            "digests must contain at least one digest".to_string(),
+        ));
+    }
+
+    let mut seen = HashSet::new();
+    let digests: Vec<String> = digests
+        .into_iter()
+        .filter(|digest| seen.insert(digest.clone()))
+        .collect();
+
+    if digests.len() > max_size {
+        return Err(ExplainError::InvalidBatch(format!(
+            "{} digests requested, at most {} are allowed",
+            digests.len(),
+            max_size
+        )));
+    }
+
+    Ok(digests)
+}
+
+fn ndjson_line<T: Serialize>(value: &T) -> Vec<u8> {
+    let mut line = serde_json::to_vec(value).unwrap_or_default();
+    line.push(b'\n');
+    line
+}
+
+fn accepts(headers: &HeaderMap, content_type: &str) -> bool {
+    headers
+        .get(header::ACCEPT)
+        .and_then(|v| v.to_str().ok())
+        .is_some_and(|v| v.contains(content_type))
+}
+
+// Same fields as the JSON body (named, so maps stay maps), just smaller on the wire
+fn msgpack_response<T: Serialize>(status: StatusCode, body: &T) -> Response {
+    match rmp_serde::to_vec_named(body) {
+        Ok(bytes) => (status, [(header::CONTENT_TYPE, MSGPACK)], bytes).into_response(),
+        Err(e) => (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            format!("Failed to serialize response: {}", e),
+        )
+            .into_response(),
+    }
+}
+
+// Serialize the body ourselves (instead of axum's Json) so it can be pretty-printed on request.
+// Compact is the default unless DEFAULT_PRETTY=true is set.
+fn json_response<T: Serialize>(status: StatusCode, body: &T, pretty: Option<bool>) -> Response {
+    let pretty = pretty.unwrap_or_else(|| DEFAULT_PRETTY.get().copied().unwrap_or(false));
+
+    let json = if pretty {
+        serde_json::to_string_pretty(body)
+    } else {
+        serde_json::to_string(body)
+    };
+
+    match json {
+        Ok(json) => (status, [(header::CONTENT_TYPE, "application/json")], json).into_response(),
+        Err(e) => (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            format!("Failed to serialize response: {}", e),
+        )
+            .into_response(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fixtures::{MockFetcher, digest, test_client};
+    use std::time::Duration;
+
+    async fn explain(fetcher: MockFetcher, digest: &str) -> (StatusCode, serde_json::Value) {
+        let response = explain_transaction(
+            State(test_client(Arc::new(fetcher))),
+            Extension(RequestId("test".to_string())),
+            HeaderMap::new(),
+            Query(FormatParams { pretty: None }),
+            Json(ExplainRequest {
+                digest: digest.to_string(),
+                options: ExplainOptions::default(),
+            }),
+        )
+        .await;
+
+        let status = response.status();
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        (status, serde_json::from_slice(&body).unwrap())
+    }
+
+    #[tokio::test]
+    async fn unknown_digest_is_not_found() {
+        let (status, body) = explain(MockFetcher::default(), &digest(1).to_string()).await;
+        assert_eq!(status, StatusCode::NOT_FOUND);
+        assert_eq!(body["success"], false);
+        assert_eq!(body["error_code"], "NOT_FOUND");
+        assert_eq!(
+            body["error"],
+            format!("Transaction {} not found", digest(1))
+        );
+    }
+
+    #[test]
+    fn not_found_names_what_was_looked_up() {
+        let e = ExplainError::NotFound("Package 0x2".to_string());
+        assert_eq!(e.to_string(), "Package 0x2 not found");
+        assert_eq!(e.status(), StatusCode::NOT_FOUND);
+    }
+
+    #[tokio::test]
+    async fn malformed_digest_is_a_bad_request() {
+        let (status, body) = explain(MockFetcher::default(), "not-a-digest").await;
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+        assert_eq!(body["error_code"], "INVALID_DIGEST");
+    }
+
+    #[tokio::test]
+    async fn unreachable_node_is_unavailable() {
+        let fetcher =
+            MockFetcher::default().failing(|| ExplainError::Connection("refused".to_string()));
+        let (status, body) = explain(fetcher, &digest(1).to_string()).await;
+        assert_eq!(status, StatusCode::SERVICE_UNAVAILABLE);
+        assert_eq!(body["error_code"], "CONNECTION_FAILED");
+    }
+
+    #[tokio::test]
+    async fn batch_explains_at_most_batch_concurrency_at_once() {
+        let fetcher = Arc::new(MockFetcher::default().with_delay(Duration::from_millis(20)));
+        let client = test_client(fetcher.clone());
+        let concurrency = client.config().batch_concurrency;
+        let digests: Vec<String> = (0..concurrency as u8 * 3)
+            .map(|seed| digest(seed).to_string())
+            .collect();
+
+        let response = explain_batch(
+            State(client),
+            Extension(RequestId("test".to_string())),
+            HeaderMap::new(),
+            Query(FormatParams { pretty: None }),
+            Json(BatchExplainRequest {
+                digests,
+                only_anomalies: false,
+                high_gas_mist: None,
+                high_gas: None,
+                categories: vec![],
+                options: ExplainOptions::default(),
+            }),
+        )
+        .await;
+        assert_eq!(response.status(), StatusCode::OK);
+        axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+
+        assert!(fetcher.max_in_flight() <= concurrency);
+        // More than one, or the bound wouldn't have been tested
+        assert!(fetcher.max_in_flight() > 1);
+    }
+
+    #[tokio::test]
+    async fn rejected_batch_echoes_the_request_id() {
+        let response = explain_batch(
+            State(test_client(Arc::new(MockFetcher::default()))),
+            Extension(RequestId("test".to_string())),
+            HeaderMap::new(),
+            Query(FormatParams { pretty: None }),
+            Json(BatchExplainRequest {
+                digests: vec![],
+                only_anomalies: false,
+                high_gas_mist: None,
+                high_gas: None,
+                categories: vec![],
+                options: ExplainOptions::default(),
+            }),
+        )
+        .await;
+        assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        let body: serde_json::Value = serde_json::from_slice(&body).unwrap();
+        assert_eq!(body["request_id"], "test");
+    }
 }
@@ -0,0 +1,26 @@
+// The explainer as a library: what the server in main.rs is built from, for embedding it in wallets,
+// indexers or scripts. SuiClient explains transactions by digest, decode::explain_transaction_data
+// explains unsigned transaction data without any RPC.
+
+pub mod abort_codes;
+pub mod address_labels;
+pub mod api_key;
+pub mod cache;
+pub mod commands;
+pub mod config;
+pub mod decode;
+pub mod diff;
+pub mod enricher;
+pub mod error;
+pub mod explorer;
+#[cfg(test)]
+mod fixtures;
+pub mod format;
+pub mod handlers;
+pub mod models;
+pub mod pure_args;
+pub mod request_id;
+pub mod rpc;
+pub mod safety;
+pub mod sui_client;
+pub mod system_objects;
@@ -1,24 +1,104 @@
 use axum::{
     Router,
-    routing::{get, post},
+    extract::DefaultBodyLimit,
+    middleware,
+    routing::{any, get, post},
+};
+use std::{net::SocketAddr, path::Path, sync::Arc};
+use tower_http::{
+    compression::{
+        CompressionLayer,
+        predicate::{DefaultPredicate, NotForContentType, Predicate},
+    },
+    cors::CorsLayer,
+    limit::RequestBodyLimitLayer,
+    services::ServeDir,
 };
-use std::net::SocketAddr;
-use tower_http::{cors::CorsLayer, services::ServeDir};
 
-mod handlers;
-mod models;
-mod sui_client;
+use sui_readable::{api_key, config, handlers, request_id, sui_client};
 
 #[tokio::main]
 async fn main() {
     println!("Starting Sui Readable server...");
 
+    // Every setting is read and checked here, once, so a bad one stops the server before it listens
+    let config = match config::Config::from_env() {
+        Ok(config) => Arc::new(config),
+        Err(e) => {
+            eprint!("{}", e);
+            std::process::exit(1);
+        }
+    };
+    handlers::set_default_pretty(config.default_pretty);
+
+    // One client for the whole server, so RPC connections and caches are shared between requests
+    let client = Arc::new(sui_client::SuiClient::new(config.clone()));
+
+    let body_limit = config.body_limit;
+    let tx_body_limit = config.tx_body_limit;
+
     // Build our application router with routes
-    let app = Router::new()
+    let mut app = Router::new()
         // API routes
         .route("/api/explain", post(handlers::explain_transaction)) // POST endpoint for explaining
+        .route("/api/explain/batch", post(handlers::explain_batch)) // POST endpoint for many digests at once
+        .route("/api/status/:digest", get(handlers::transaction_status)) // GET endpoint for status only
+        .route("/api/calls/:digest", get(handlers::transaction_calls)) // GET endpoint for Move calls only
+        .route("/api/object/:id/last-tx", get(handlers::object_last_tx)) // GET endpoint starting from an object
+        .route(
+            "/api/address/:address/history",
+            get(handlers::address_history),
+        ) // GET endpoint for what an address sent
+        .route("/api/diff", post(handlers::diff_transactions)) // POST endpoint comparing two digests
+        .route("/api/diff/dry-run", post(handlers::diff_dry_run)) // POST endpoint comparing with a dry run
+        .route("/api/pnl", post(handlers::profit_and_loss)) // POST endpoint for net gains and losses
+        .route("/api/stats", post(handlers::transaction_stats)) // POST endpoint for aggregate statistics
         .route("/api/health", get(handlers::health_check)) // GET endpoint for health
-        .nest_service("/", ServeDir::new("static"))
+        // Body limit for the routes above, which only carry digests and flags. axum's Json has its own
+        // 2 MiB default, raised (or lowered) to match so a larger setting isn't silently capped.
+        .route_layer(RequestBodyLimitLayer::new(body_limit))
+        .route_layer(DefaultBodyLimit::max(body_limit))
+        // POST endpoint for offline BCS decoding, transaction bytes need more room
+        .route(
+            "/api/decode",
+            post(handlers::decode_transaction)
+                .layer(RequestBodyLimitLayer::new(tx_body_limit))
+                .layer(DefaultBodyLimit::max(tx_body_limit)),
+        )
+        // POST endpoint for previewing gas and effects, same room as decoding
+        .route(
+            "/api/dry-run",
+            post(handlers::dry_run_transaction)
+                .layer(RequestBodyLimitLayer::new(tx_body_limit))
+                .layer(DefaultBodyLimit::max(tx_body_limit)),
+        )
+        .route("/api/*rest", any(handlers::route_not_found)) // JSON 404 for any other API path
+        .method_not_allowed_fallback(handlers::method_not_allowed) // JSON 405 for a wrong method
+        .with_state(client);
+
+    // Serve the frontend only when its directory is actually there
+    let static_dir = &config.static_dir;
+    if Path::new(&static_dir).is_dir() {
+        app = app.nest_service("/", ServeDir::new(static_dir));
+    } else {
+        eprintln!(
+            "Warning: static directory '{}' not found, serving the API only",
+            static_dir
+        );
+    }
+
+    let app = app
+        // With API_KEYS set, /api/* (except health) needs a matching X-API-Key
+        .layer(middleware::from_fn_with_state(
+            api_key::ApiKeys::new(config.api_keys.clone()),
+            api_key::require_api_key,
+        ))
+        // Tag every request with an ID for log correlation
+        .layer(middleware::from_fn(request_id::request_id))
+        // gzip/brotli when the client asks for it, except NDJSON so streamed lines aren't held back
+        .layer(CompressionLayer::new().compress_when(
+            DefaultPredicate::new().and(NotForContentType::const_new("application/x-ndjson")),
+        ))
         // Enable CORS so frontend can call our API
         .layer(CorsLayer::permissive());
 
@@ -1,9 +1,146 @@
 use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
 
 // What the user sends (transaction digest)
 #[derive(Debug, Deserialize)]
 pub struct ExplainRequest {
     pub digest: String,
+    #[serde(flatten)]
+    pub options: ExplainOptions,
+}
+
+// Optional flags controlling how much work goes into an explanation
+#[derive(Debug, Deserialize, Clone)]
+#[serde(default)]
+pub struct ExplainOptions {
+    pub simplify: bool, //Friendly type labels ("SUI Coin") in object_type/coin_type, false keeps full types
+    pub resolve_display: bool, //Fetch Display fields for created objects (costs extra RPC calls)
+    pub wait: bool, //Poll for transactions that aren't indexed yet instead of failing with not found
+    pub wait_timeout_ms: Option<u64>, //How long to wait (default 10s, max 30s)
+    pub status_only: bool, //Only fetch effects: status and gas, no object/balance/event parsing
+    pub include_effects: bool, //Attach the serialized effects as raw_effects
+    pub group_digits: bool, //Thousands separators in *_readable amounts, false for machine consumers
+    pub include_sender_balance: bool, //Look up the sender's balances as of now (one extra RPC call)
+    pub include_context: bool, //Summaries of the sender's transactions right before and after this one
+    pub context_size: Option<usize>, //How many on each side (default 3, max 10)
+    pub watchlist: Vec<String>, //Addresses to report in matched_addresses when the transaction involves them
+    pub verbosity: Option<String>, //Summary length: "terse", "normal" (default) or "detailed"
+    pub object_type_filter: Vec<String>, //Keep only object changes whose simplified type contains one of these
+    pub include_checkpoint_position: bool, //Find the transaction's index in its checkpoint (one extra RPC call)
+    pub sui_precision: Option<usize>, //Decimals in SUI amounts (default 6 or SUI_PRECISION, max 9)
+    pub trim_zeros: bool, //Drop trailing zeros from SUI amounts, "1.000000 SUI" -> "1 SUI"
+    pub activity: bool,   //Also return the transaction as flat activity items for feeds
+    pub amount_unit: Option<String>, //SUI amounts as "sui" (default) or raw "mist" integers
+    pub group_by_owner: bool, //Also return object and balance changes grouped per owner in by_owner
+    pub check_node_lag: bool, //Report the serving node's latest checkpoint in meta (one extra RPC call)
+}
+
+impl Default for ExplainOptions {
+    fn default() -> Self {
+        Self {
+            simplify: true,
+            resolve_display: false,
+            wait: false,
+            wait_timeout_ms: None,
+            status_only: false,
+            include_effects: false,
+            group_digits: true,
+            include_sender_balance: false,
+            include_context: false,
+            context_size: None,
+            watchlist: vec![],
+            verbosity: None,
+            object_type_filter: vec![],
+            include_checkpoint_position: false,
+            sui_precision: None,
+            trim_zeros: false,
+            activity: false,
+            amount_unit: None,
+            group_by_owner: false,
+            check_node_lag: false,
+        }
+    }
+}
+
+// Query parameters controlling the JSON output (?pretty=true)
+#[derive(Debug, Deserialize)]
+pub struct FormatParams {
+    pub pretty: Option<bool>,
+}
+
+// Query parameters of GET /api/calls/:digest (?function=module::function)
+#[derive(Debug, Deserialize)]
+pub struct CallsParams {
+    pub function: Option<String>, //Only calls to this function, with their arguments decoded
+    pub command_offset: Option<usize>, //Move calls to skip, for paging through huge PTBs
+    pub command_limit: Option<usize>, //Move calls to look at, all by default. function filters within the page
+}
+
+// Query parameters of GET /api/address/:address/history
+#[derive(Debug, Deserialize)]
+pub struct HistoryParams {
+    pub limit: Option<usize>,
+    pub from_ms: Option<u64>, //Only transactions at or after this time, ms since the Unix epoch
+    pub to_ms: Option<u64>,   //Only transactions at or before this time
+}
+
+// What the user sends for a batch (list of transaction digests)
+#[derive(Debug, Deserialize)]
+pub struct BatchExplainRequest {
+    pub digests: Vec<String>,
+    #[serde(default)]
+    pub only_anomalies: bool, //Leave out routine successes, see the batch handler for what counts
+    pub high_gas_mist: Option<u64>, //Gas above this is an anomaly (default 0.1 SUI)
+    pub high_gas: Option<String>,   //The same threshold as typed, "0.05 SUI" or "50000000 MIST"
+    #[serde(default)]
+    pub categories: Vec<String>, //Categories that count as anomalies, e.g. ["Package Upgrade"]
+    #[serde(flatten)]
+    pub options: ExplainOptions,
+}
+
+// What the user sends for a profit/loss report on one address
+#[derive(Debug, Deserialize)]
+pub struct PnlRequest {
+    pub address: String,
+    #[serde(default)]
+    pub digests: Vec<String>,
+    pub from_ms: Option<u64>, //Without digests: every transaction the address sent or received from then on
+    pub to_ms: Option<u64>,   //Without digests: up to then
+    #[serde(flatten)]
+    pub options: ExplainOptions,
+}
+
+// What the user sends for aggregate statistics over a set of transactions
+#[derive(Debug, Deserialize)]
+pub struct StatsRequest {
+    pub digests: Vec<String>,
+    #[serde(flatten)]
+    pub options: ExplainOptions,
+}
+
+// What the user sends to compare two transactions
+#[derive(Debug, Deserialize)]
+pub struct DiffRequest {
+    pub left: String,
+    pub right: String,
+    #[serde(flatten)]
+    pub options: ExplainOptions,
+}
+
+// What the user sends to decode transaction bytes offline
+#[derive(Debug, Deserialize)]
+pub struct DecodeRequest {
+    pub tx_bytes: String, //Base64 BCS of SenderSignedData or TransactionData
+    #[serde(default)]
+    pub decode_args: bool, //Look up called functions to decode pure inputs
+}
+
+// What the user sends to preview a transaction against the current chain state
+#[derive(Debug, Deserialize)]
+pub struct DryRunRequest {
+    pub tx_bytes: String, //Base64 BCS of SenderSignedData or TransactionData
+    #[serde(flatten)]
+    pub options: ExplainOptions,
 }
 
 // What is returned to the user
@@ -11,37 +148,367 @@ pub struct ExplainRequest {
 pub struct ExplainResponse {
     pub success: bool,
     pub explanation: Option<TransactionExplanation>,
-    pub error: Option<String>, //Display error if transaction fails
+    pub error: Option<String>,      //Display error if transaction fails
+    pub error_code: Option<String>, //Stable error category, e.g. "NOT_FOUND" or "INVALID_DIGEST"
+    pub meta: Option<ResponseMeta>,
+    pub request_id: Option<String>, //Same ID as the X-Request-Id response header
+}
+
+// Where the answer came from
+#[derive(Debug, Serialize, Clone)]
+pub struct ResponseMeta {
+    pub rpc_endpoint: String, //The RPC endpoint that actually served the request
+    pub source: String,       //"fullnode", or "history" when the full node had pruned it
+    pub cached: bool,         //Served from the explanation cache without asking the node
+    pub node_latest_checkpoint: Option<u64>, //Newest checkpoint the node has, when check_node_lag is set
+    pub node_lag_ms: Option<u64>,            //How long ago that checkpoint was produced
+    pub node_behind: bool, //The lag exceeds NODE_MAX_LAG_MS, recent transactions may be missing
+}
+
+// Lightweight answer for GET /api/status/:digest
+#[derive(Debug, Serialize)]
+pub struct StatusResponse {
+    pub success: bool,
+    pub status: Option<TransactionStatus>,
+    pub error: Option<String>,
+    pub error_code: Option<String>,
+    pub request_id: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct TransactionStatus {
+    pub digest: String,
+    pub status: String,
+    pub gas_used_sui: String,
+}
+
+// Answer for GET /api/calls/:digest
+#[derive(Debug, Serialize)]
+pub struct CallsResponse {
+    pub success: bool,
+    pub digest: String,
+    pub calls: Vec<MoveCall>, //Empty for transactions that aren't PTBs
+    pub total: usize,         //Every Move call in the transaction, to count pages
+    pub error: Option<String>,
+    pub error_code: Option<String>,
+    pub request_id: Option<String>,
+}
+
+// A single Move function call from a PTB
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct MoveCall {
+    pub package: String,
+    pub module: String,
+    pub function: String,
+    pub type_args: Vec<String>,
+    pub args: Vec<String>, //PTB arguments, e.g. "Input(0)" or "Result(1)"
+    pub decoded_args: Option<Vec<String>>, //Args with pure inputs decoded, when requested
+}
+
+// Answer for POST /api/decode
+#[derive(Debug, Serialize)]
+pub struct DecodeResponse {
+    pub success: bool,
+    pub decoded: Option<DecodedTransaction>,
+    pub error: Option<String>,
+    pub error_code: Option<String>,
+    pub request_id: Option<String>,
+}
+
+// Answer for POST /api/dry-run. gas is filled in whenever the dry run executed, failed or not,
+// so a wallet can show both the failure reason and what the attempt would have cost.
+#[derive(Debug, Serialize)]
+pub struct DryRunResponse {
+    pub success: bool, //The dry run itself ran, check explanation.status for the transaction outcome
+    pub explanation: Option<TransactionExplanation>,
+    pub gas: Option<GasBreakdown>,
+    pub error: Option<String>,
+    pub error_code: Option<String>,
+    pub request_id: Option<String>,
+}
+
+// Estimated gas, all amounts in MIST
+#[derive(Debug, Serialize)]
+pub struct GasBreakdown {
+    pub computation_cost: u64,
+    pub storage_cost: u64,
+    pub storage_rebate: u64,
+    pub non_refundable_storage_fee: u64,
+    pub gas_used: u64, //computation + storage - rebate, same as TransactionExplanation.gas_used
+    pub net_rebate: u64, //What the rebate pays back beyond the costs, 0 unless storage was freed
+    pub gas_used_sui: String,
+    pub gas_price: u64,         //MIST per gas unit the transaction offered
+    pub gas_units: Option<u64>, //gas_used / gas_price, None when the price is zero
+}
+
+// A transaction as it will run, before execution, so there are no effects
+#[derive(Debug, Serialize)]
+pub struct DecodedTransaction {
+    pub sender: String,
+    pub gas: GasInfo,
+    pub is_programmable: bool,
+    pub command_summary: BTreeMap<String, usize>,
+    pub commands: Vec<String>, //Command kinds in execution order
+    pub calls: Vec<MoveCall>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct GasInfo {
+    pub owner: String,
+    pub budget: u64,          //In MIST
+    pub price: u64,           //In MIST per gas unit
+    pub payment: Vec<String>, //Gas coin object IDs
+}
+
+// One entry of a batch response, tagged with the digest it belongs to
+#[derive(Debug, Serialize)]
+pub struct BatchExplainItem {
+    pub digest: String,
+    #[serde(flatten)]
+    pub response: ExplainResponse,
+}
+
+// How much of a batch only_anomalies left out
+#[derive(Debug, Serialize)]
+pub struct BatchSummary {
+    pub total: usize,
+    pub omitted: usize,
+}
+
+// Batch answer with only_anomalies, the kept items plus how many were omitted
+#[derive(Debug, Serialize)]
+pub struct AnomalyBatchResponse {
+    pub items: Vec<BatchExplainItem>,
+    #[serde(flatten)]
+    pub summary: BatchSummary,
 }
 
 // Explanation of the transaction, including its effects and any relevant details
 #[derive(Debug, Serialize, Deserialize, Clone, Default)]
 pub struct TransactionExplanation {
     pub digest: String,
-    pub sender: String,
+    pub sender: Option<String>, //None when the node no longer has the transaction data
+    pub kind: String,           //"ProgrammableTransaction", "ChangeEpoch", ...
+    pub signers: Vec<String>,   //Signing addresses, multisig members expanded
+    pub sponsor: Option<String>, //Gas owner when it isn't the sender (sponsored transaction)
+    pub threshold: Option<u16>, //Multisig weight threshold, None for single-key signatures
+    pub signature_schemes: Vec<String>, //One per signature: "ED25519", "Secp256k1", "MultiSig", ...
+    pub data_available: bool,   //False when only effects were available (e.g. a pruned node)
+    pub available_sections: Vec<String>, //Parts the node answered with: "input", "effects", "events", ...
+    pub checkpoint: Option<u64>, //Checkpoint sequence number, None until the transaction is checkpointed
+    pub checkpoint_index: Option<usize>, //Position within the checkpoint, when include_checkpoint_position is set
     pub status: String,
-    pub gas_used: u64,        //Total gas used in MIST (1 SUI = 1,000,000,000 MIST)
-    pub gas_used_sui: String, //Total gas used in SUI, simple and more readable
+    pub failure_reason: Option<String>, //Readable Move abort reason when the transaction failed
+    pub gas_used: u64,                  //Total gas used in MIST (1 SUI = 1,000,000,000 MIST)
+    pub gas_used_sui: String,           //Total gas used in SUI, simple and more readable
+    pub gas_price: Option<u64>, //MIST per gas unit set in the gas data, None without the transaction data
+    pub gas_units: Option<u64>, //gas_used / gas_price, comparable across gas prices
+    pub computation_units: Option<u64>, //Computation cost / gas price, None without the transaction data
+    pub command_summary: BTreeMap<String, usize>, //PTB commands by kind, e.g. {"MoveCall": 3}
+    pub shared_objects: Vec<String>,    //Shared inputs, system ones named, e.g. "Clock (0x6)"
+    pub packages: Vec<String>,          //Packages called or emitting events, labeled when known
     pub actions: Vec<String>,
     pub object_changes: Vec<ObjectMod>,
     pub balance_changes: Vec<BalanceChange>,
+    pub coin_flow: Vec<CoinFlow>, //Sender's net movement per coin, gas excluded
+    pub sender_balances: Option<Vec<SenderBalance>>, //Current balances, not as of the transaction
+    pub context: Vec<ContextTransaction>, //Sender's neighbouring transactions, oldest first
+    pub matched_addresses: Vec<String>, //Watchlist entries seen as sender, signer, recipient or owner
+    pub unique_addresses: usize,        //Distinct sender, signer and owner addresses
     pub events: Vec<String>,
+    pub event_details: Vec<EventInfo>,           //Structured events
+    pub events_by_type: BTreeMap<String, usize>, //Events by simplified type, e.g. {"SwapEvent": 2}
+    pub category: Option<String>, //Recognized kind of transaction, e.g. "Package Upgrade"
+    pub activity: Option<Vec<ActivityItem>>, //One item per user-facing action, when requested
+    pub by_owner: Vec<OwnerChanges>, //Changes per owner, sender first, when group_by_owner is set
+    pub warnings: Vec<String>,    //Advisory safety flags, heuristics rather than a verdict
     pub summary: String,
+    pub links: ExplorerLinks,
+    pub raw_effects: Option<serde_json::Value>, //Full SuiTransactionBlockEffects when include_effects is set
+}
+
+// An emitted event, for consumers matching on exact types
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct EventInfo {
+    pub event_type: String, //Simplified name for display, e.g. "SwapEvent"
+    pub full_type: String,  //Canonical StructTag, full package address and type arguments included
+    pub package_id: String,
+    pub module: String, //Module of the call that emitted it
+    pub sender: String,
+}
+
+// One user-facing action for timeline rendering, e.g. {actor: 0xalice, verb: "sent", amount: "5 SUI", counterparty: 0xbob}
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct ActivityItem {
+    pub actor: String,
+    pub verb: String, //"sent", "received", "created", "transferred" or "deleted"
+    pub object: Option<String>, //Object type the action is about, None for coin movements
+    pub counterparty: Option<String>, //The other side, when there is exactly one
+    pub amount: Option<String>, //Amount with its unit, e.g. "5.000000 SUI"
+    pub timestamp: Option<u64>, //Milliseconds since epoch, None until checkpointed
+}
+
+// Everything that happened to one participant's objects and balances, for multi-party transactions.
+// `owner` is an address, or the owner as shown in object changes for shared, immutable and
+// object-owned objects.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct OwnerChanges {
+    pub owner: String,
+    pub object_changes: Vec<ObjectMod>,
+    pub balance_changes: Vec<BalanceChange>,
+}
+
+// Block explorer URLs for everything the transaction touched
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+pub struct ExplorerLinks {
+    pub transaction: String,
+    pub sender: Option<String>,
+    pub objects: BTreeMap<String, String>, //Object ID -> explorer URL
+    pub packages: BTreeMap<String, String>, //Package ID -> explorer URL
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct ObjectMod {
     pub change_type: String,
     pub object_type: String,
+    pub full_type: String, //Exact Move type, kept even when object_type is simplified
     pub object_id: String,
     pub owner: Option<String>,
     pub details: String,
+    pub display: Option<BTreeMap<String, String>>, //Resolved Display fields (name, image_url, ...) when requested
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct BalanceChange {
     pub owner: String,
     pub coin_type: String,
+    pub full_type: String, //Exact coin type, kept even when coin_type is simplified
     pub amount: i128, //Using signed integer here because there's two considered BalanceChange (Sent, Received)
+    pub direction: String, //"Received", "Sent" or "None" for a zero amount, same as the sign
+    pub amount_readable: String,
+    pub decimals_source: String, //"metadata" (on-chain CoinMetadata), "builtin" (SUI) or "fallback" (raw)
+    pub is_gas: bool, //True when this entry is only the gas payment, not SUI actually sent
+    pub is_sponsor: bool, //True when the change belongs to the sponsor paying for someone else's transaction
+}
+
+// Net amount of one coin that left or reached the sender, so multi-hop swaps read as "X out, Y in"
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct CoinFlow {
+    pub coin: String,
+    pub full_type: String, //Exact coin type, kept even when coin is simplified
+    pub direction: String, //"In" or "Out"
+    pub amount: i128,      //Signed net amount, negative when the coin went out
     pub amount_readable: String,
 }
+
+// One coin the sender holds right now, fetched when include_sender_balance is set
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct SenderBalance {
+    pub coin_type: String,
+    pub full_type: String,
+    pub balance: u128,
+    pub balance_readable: String,
+}
+
+// A transaction the sender made just before or after the explained one
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct ContextTransaction {
+    pub digest: String,
+    pub summary: String,
+    pub timestamp: Option<u64>, //Checkpoint time in milliseconds since the Unix epoch
+    pub warnings: Vec<String>,  //Safety flags, as on a full explanation
+}
+
+// Answer for GET /api/address/:address/history
+#[derive(Debug, Serialize)]
+pub struct HistoryResponse {
+    pub success: bool,
+    pub address: String,
+    pub transactions: Vec<ContextTransaction>, //Newest first
+    pub error: Option<String>,
+    pub error_code: Option<String>,
+    pub request_id: Option<String>,
+}
+
+// Net gain or loss of one address across a set of transactions
+#[derive(Debug, Serialize)]
+pub struct PnlResponse {
+    pub success: bool,
+    pub address: String,
+    pub net: Vec<NetAmount>,
+    pub transaction_count: usize,
+    pub error: Option<String>,
+    pub error_code: Option<String>,
+    pub request_id: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct NetAmount {
+    pub coin: String,
+    pub amount: i128, //Signed total over all transactions, gas included
+    pub amount_readable: String,
+}
+
+// Answer for POST /api/stats
+#[derive(Debug, Serialize)]
+pub struct StatsResponse {
+    pub success: bool,
+    pub stats: Option<BatchStats>,
+    pub error: Option<String>,
+    pub error_code: Option<String>,
+    pub request_id: Option<String>,
+}
+
+// Rollup over a set of transactions
+#[derive(Debug, Serialize, Default)]
+pub struct BatchStats {
+    pub transaction_count: usize,
+    pub succeeded: usize,
+    pub failed: usize,
+    pub errors: usize, //Digests that couldn't be explained (not found, pruned, ...)
+    pub total_gas_used: u64, //MIST, over every explained transaction
+    pub total_gas_used_sui: String,
+    pub categories: BTreeMap<String, usize>, //Category -> count, "Other" when none was recognized
+    pub value_moved: Vec<NetAmount>,         //Per coin, everything any owner received, gas excluded
+}
+
+// Side-by-side comparison of two explained transactions
+#[derive(Debug, Serialize)]
+pub struct DiffResponse {
+    pub success: bool,
+    pub diff: Option<TransactionDiff>,
+    pub error: Option<String>,
+    pub error_code: Option<String>,
+    pub request_id: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct TransactionDiff {
+    pub left_digest: String,
+    pub right_digest: String,
+    pub status: ValueDiff<String>,
+    pub gas_used: ValueDiff<u64>,
+    pub gas_delta: i128, //Right minus left, in MIST
+    pub object_changes: ListDiff<ObjectMod>,
+    pub balance_changes: ListDiff<BalanceChange>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ValueDiff<T> {
+    pub left: T,
+    pub right: T,
+    pub changed: bool,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ListDiff<T> {
+    pub added: Vec<T>,   //Only in the right transaction
+    pub removed: Vec<T>, //Only in the left transaction
+    pub changed: Vec<ChangedEntry<T>>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ChangedEntry<T> {
+    pub left: T,
+    pub right: T,
+}
@@ -0,0 +1,288 @@
+use sui_json_rpc_types::SuiMoveNormalizedType;
+
+// Decoding of pure (non-object) PTB inputs, which are plain BCS bytes typed by the function consuming them
+
+//Render a pure input as its Move type, e.g. "42", "true", "0xab..", "\"hello\"", "[1, 2]" or "Some(5)".
+//Types we don't know and bytes that don't fit the type are shown as hex.
+pub fn decode_pure_arg(bytes: &[u8], type_name: &str) -> String {
+    let mut input = bytes;
+    match decode_value(&mut input, type_name.trim()) {
+        Some(value) if input.is_empty() => value,
+        _ => hex(bytes),
+    }
+}
+
+//Move type name of a function parameter, with the call's type arguments filled in for generics
+pub fn normalized_type_name(ty: &SuiMoveNormalizedType, type_args: &[String]) -> Option<String> {
+    // Go through the JSON form so we don't depend on the SDK's struct layout
+    let value = serde_json::to_value(ty).ok()?;
+    json_type_name(&value, type_args)
+}
+
+fn json_type_name(value: &serde_json::Value, type_args: &[String]) -> Option<String> {
+    if let Some(primitive) = value.as_str() {
+        return Some(primitive.to_lowercase());
+    }
+
+    let (kind, inner) = value.as_object()?.iter().next()?;
+    match kind.as_str() {
+        "Vector" => Some(format!("vector<{}>", json_type_name(inner, type_args)?)),
+        "Reference" | "MutableReference" => json_type_name(inner, type_args),
+        "TypeParameter" => type_args
+            .get(inner.as_u64()? as usize)
+            .map(|arg| short_type_name(arg)),
+        "Struct" => {
+            let address = short_address(inner.get("address")?.as_str()?);
+            let module = inner.get("module")?.as_str()?;
+            let name = inner.get("name")?.as_str()?;
+            let args = inner
+                .get("typeArguments")
+                .and_then(|args| args.as_array())
+                .map(|args| {
+                    args.iter()
+                        .filter_map(|arg| json_type_name(arg, type_args))
+                        .collect::<Vec<_>>()
+                })
+                .unwrap_or_default();
+            if args.is_empty() {
+                Some(format!("{}::{}::{}", address, module, name))
+            } else {
+                Some(format!(
+                    "{}::{}::{}<{}>",
+                    address,
+                    module,
+                    name,
+                    args.join(", ")
+                ))
+            }
+        }
+        _ => None,
+    }
+}
+
+fn decode_value(input: &mut &[u8], ty: &str) -> Option<String> {
+    let value = match ty {
+        "bool" => match take(input, 1)?[0] {
+            0 => "false".to_string(),
+            1 => "true".to_string(),
+            _ => return None,
+        },
+        "u8" => take(input, 1)?[0].to_string(),
+        "u16" => u16::from_le_bytes(take(input, 2)?.try_into().ok()?).to_string(),
+        "u32" => u32::from_le_bytes(take(input, 4)?.try_into().ok()?).to_string(),
+        "u64" => u64::from_le_bytes(take(input, 8)?.try_into().ok()?).to_string(),
+        "u128" => u128::from_le_bytes(take(input, 16)?.try_into().ok()?).to_string(),
+        "u256" => {
+            let bytes = take(input, 32)?;
+            // Shown in decimal when it fits a u128, otherwise as big-endian hex
+            if bytes[16..].iter().all(|b| *b == 0) {
+                u128::from_le_bytes(bytes[..16].try_into().ok()?).to_string()
+            } else {
+                let big_endian: Vec<u8> = bytes.iter().rev().copied().collect();
+                hex(&big_endian)
+            }
+        }
+        "address" | "0x2::object::ID" => hex(take(input, 32)?),
+        "0x1::string::String" | "0x1::ascii::String" => {
+            let len = uleb128(input)?;
+            let text = std::str::from_utf8(take(input, len)?).ok()?;
+            format!("{:?}", text)
+        }
+        _ => {
+            if let Some(inner) = generic_arg(ty, "vector") {
+                let len = uleb128(input)?;
+                if inner == "u8" {
+                    return Some(byte_vector(take(input, len)?));
+                }
+                let items = (0..len)
+                    .map(|_| decode_value(input, inner))
+                    .collect::<Option<Vec<_>>>()?;
+                format!("[{}]", items.join(", "))
+            } else if let Some(inner) = generic_arg(ty, "0x1::option::Option") {
+                // Options are BCS vectors of zero or one element
+                match uleb128(input)? {
+                    0 => "None".to_string(),
+                    1 => format!("Some({})", decode_value(input, inner)?),
+                    _ => return None,
+                }
+            } else {
+                return None;
+            }
+        }
+    };
+    Some(value)
+}
+
+//vector<u8> is often text (names, URLs) passed as bytes: quote it when it reads as text, hex otherwise
+fn byte_vector(bytes: &[u8]) -> String {
+    if bytes.is_empty() {
+        return "[]".to_string();
+    }
+    match std::str::from_utf8(bytes) {
+        Ok(text) if text.chars().all(|c| !c.is_control() || c == '\n') => format!("{:?}", text),
+        _ => hex(bytes),
+    }
+}
+
+//"vector<u64>" with outer "vector" gives "u64"
+fn generic_arg<'a>(ty: &'a str, outer: &str) -> Option<&'a str> {
+    ty.strip_prefix(outer)?
+        .strip_prefix('<')?
+        .strip_suffix('>')
+        .map(str::trim)
+}
+
+fn take<'a>(input: &mut &'a [u8], len: usize) -> Option<&'a [u8]> {
+    if input.len() < len {
+        return None;
+    }
+    let (head, rest) = input.split_at(len);
+    *input = rest;
+    Some(head)
+}
+
+// BCS length prefixes are ULEB128
+fn uleb128(input: &mut &[u8]) -> Option<usize> {
+    let mut value: u64 = 0;
+    for shift in (0..64).step_by(7) {
+        let byte = take(input, 1)?[0];
+        value |= ((byte & 0x7f) as u64) << shift;
+        if byte & 0x80 == 0 {
+            return usize::try_from(value).ok();
+        }
+    }
+    None
+}
+
+fn hex(bytes: &[u8]) -> String {
+    let digits: String = bytes.iter().map(|b| format!("{:02x}", b)).collect();
+    format!("0x{}", digits)
+}
+
+//"0x0000..0002" -> "0x2"
+fn short_address(address: &str) -> String {
+    let digits = address.trim_start_matches("0x").trim_start_matches('0');
+    if digits.is_empty() {
+        "0x0".to_string()
+    } else {
+        format!("0x{}", digits)
+    }
+}
+
+//Shorten the package address of a type argument like "0x0000..0001::string::String"
+fn short_type_name(type_name: &str) -> String {
+    match type_name.split_once("::") {
+        Some((address, rest)) if address.starts_with("0x") => {
+            format!("{}::{}", short_address(address), rest)
+        }
+        _ => type_name.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn decodes_bools_and_integers() {
+        assert_eq!(decode_pure_arg(&[1], "bool"), "true");
+        assert_eq!(decode_pure_arg(&[0], "bool"), "false");
+        assert_eq!(decode_pure_arg(&[2], "bool"), "0x02");
+        assert_eq!(decode_pure_arg(&[42], "u8"), "42");
+        assert_eq!(decode_pure_arg(&300u16.to_le_bytes(), "u16"), "300");
+        assert_eq!(decode_pure_arg(&70_000u32.to_le_bytes(), "u32"), "70000");
+        assert_eq!(
+            decode_pure_arg(&5_000_000_000u64.to_le_bytes(), "u64"),
+            "5000000000"
+        );
+        assert_eq!(
+            decode_pure_arg(&u128::MAX.to_le_bytes(), "u128"),
+            u128::MAX.to_string()
+        );
+    }
+
+    #[test]
+    fn decodes_u256_in_decimal_until_it_outgrows_u128() {
+        let mut small = [0u8; 32];
+        small[..8].copy_from_slice(&7u64.to_le_bytes());
+        assert_eq!(decode_pure_arg(&small, "u256"), "7");
+
+        let mut large = [0u8; 32];
+        large[31] = 1;
+        assert_eq!(
+            decode_pure_arg(&large, "u256"),
+            format!("0x01{}", "00".repeat(31))
+        );
+    }
+
+    #[test]
+    fn decodes_addresses_and_strings() {
+        assert_eq!(
+            decode_pure_arg(&[0xab; 32], "address"),
+            format!("0x{}", "ab".repeat(32))
+        );
+        assert_eq!(
+            decode_pure_arg(&[0xab; 32], "0x2::object::ID"),
+            format!("0x{}", "ab".repeat(32))
+        );
+        assert_eq!(
+            decode_pure_arg(b"\x05hello", "0x1::string::String"),
+            "\"hello\""
+        );
+        assert_eq!(decode_pure_arg(b"\x00", "0x1::ascii::String"), "\"\"");
+    }
+
+    #[test]
+    fn falls_back_to_hex_when_the_bytes_dont_fit() {
+        // Too short for the type
+        assert_eq!(decode_pure_arg(&[1, 0], "u64"), "0x0100");
+        assert_eq!(
+            decode_pure_arg(b"\x05hi", "0x1::string::String"),
+            "0x056869"
+        );
+        // Bytes left over after the value
+        assert_eq!(decode_pure_arg(&[1, 0], "u8"), "0x0100");
+        assert_eq!(decode_pure_arg(&[1, 1], "bool"), "0x0101");
+        // Not valid UTF-8
+        assert_eq!(
+            decode_pure_arg(b"\x01\xff", "0x1::string::String"),
+            "0x01ff"
+        );
+        // A type we can't decode
+        assert_eq!(decode_pure_arg(&[1, 2], "0x2::coin::Coin"), "0x0102");
+    }
+
+    #[test]
+    fn shows_byte_vectors_as_text_when_they_read_as_text() {
+        assert_eq!(decode_pure_arg(&[0], "vector<u8>"), "[]");
+        assert_eq!(decode_pure_arg(b"\x05hello", "vector<u8>"), "\"hello\"");
+        assert_eq!(decode_pure_arg(b"\x03a\nb", "vector<u8>"), "\"a\\nb\"");
+        assert_eq!(decode_pure_arg(&[2, 0xff, 0x00], "vector<u8>"), "0xff00");
+        // Valid UTF-8, but control characters mean it isn't text
+        assert_eq!(decode_pure_arg(&[2, 0x01, 0x02], "vector<u8>"), "0x0102");
+    }
+
+    #[test]
+    fn decodes_nested_vectors_and_options() {
+        let mut numbers = vec![1, 2];
+        numbers.extend(1u64.to_le_bytes());
+        numbers.extend(2u64.to_le_bytes());
+        assert_eq!(
+            decode_pure_arg(&numbers, "0x1::option::Option<vector<u64>>"),
+            "Some([1, 2])"
+        );
+        assert_eq!(
+            decode_pure_arg(&[0], "0x1::option::Option<vector<u64>>"),
+            "None"
+        );
+        assert_eq!(
+            decode_pure_arg(b"\x02\x02hi\x00", "vector<vector<u8>>"),
+            "[\"hi\", []]"
+        );
+        // An option with more than one element isn't valid BCS
+        assert_eq!(
+            decode_pure_arg(&[2, 1, 1], "0x1::option::Option<u8>"),
+            "0x020101"
+        );
+    }
+}
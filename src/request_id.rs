@@ -0,0 +1,60 @@
+use axum::{extract::Request, http::HeaderValue, middleware::Next, response::Response};
+use uuid::Uuid;
+
+pub const REQUEST_ID_HEADER: &str = "x-request-id";
+
+// The ID of the current request, available to handlers as Extension<RequestId>
+#[derive(Debug, Clone)]
+pub struct RequestId(pub String);
+
+tokio::task_local! {
+    // The same ID for code that has no handle on the request, like the client and the RPC pool
+    static CURRENT: String;
+}
+
+//"[<id>] " while a request is being handled, to start log lines with. Empty outside of one.
+pub fn log_prefix() -> String {
+    CURRENT
+        .try_with(|id| format!("[{}] ", id))
+        .unwrap_or_default()
+}
+
+//Run `work` as part of request `id`. The middleware does this for every handler, streamed bodies
+//that keep working after the handler returned need it again.
+pub async fn scope<F: Future>(id: String, work: F) -> F::Output {
+    CURRENT.scope(id, work).await
+}
+
+//Take the caller's X-Request-Id (or make a UUID), log it with the request and echo it back in the response
+pub async fn request_id(mut request: Request, next: Next) -> Response {
+    let id = request
+        .headers()
+        .get(REQUEST_ID_HEADER)
+        .and_then(|v| v.to_str().ok())
+        .filter(|v| !v.is_empty() && v.len() <= 128)
+        .map(str::to_string)
+        .unwrap_or_else(|| Uuid::new_v4().to_string());
+
+    println!("[{}] {} {}", id, request.method(), request.uri().path());
+    request.extensions_mut().insert(RequestId(id.clone()));
+
+    let mut response = scope(id.clone(), next.run(request)).await;
+    println!("[{}] -> {}", id, response.status());
+
+    if let Ok(value) = HeaderValue::from_str(&id) {
+        response.headers_mut().insert(REQUEST_ID_HEADER, value);
+    }
+    response
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn log_prefix_is_set_only_inside_a_request() {
+        assert_eq!(log_prefix(), "");
+        let inside = scope("abc".to_string(), async { log_prefix() }).await;
+        assert_eq!(inside, "[abc] ");
+    }
+}
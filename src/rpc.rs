@@ -0,0 +1,211 @@
+use futures::{FutureExt, future::BoxFuture};
+use std::{
+    collections::HashMap,
+    future::Future,
+    sync::atomic::{AtomicUsize, Ordering},
+    time::Duration,
+};
+use sui_json_rpc_types::{SuiTransactionBlockResponse, SuiTransactionBlockResponseOptions};
+use sui_sdk::{SuiClient, SuiClientBuilder};
+use sui_types::digests::TransactionDigest;
+use tokio::sync::OnceCell;
+
+use crate::config::Config;
+use crate::error::ExplainError;
+use crate::request_id;
+
+// Where transactions come from. RpcPool is the real one, anything else (canned fixtures, failing
+// stubs) can be handed to SuiClient::with_fetcher so the handlers run without a network.
+// Returns the response and the endpoint that served it.
+pub trait TxFetcher: Send + Sync {
+    fn fetch<'a>(
+        &'a self,
+        digest: TransactionDigest,
+        options: &'a SuiTransactionBlockResponseOptions,
+    ) -> BoxFuture<'a, Result<(SuiTransactionBlockResponse, String), ExplainError>>;
+}
+
+struct Endpoint {
+    url: String,
+    client: OnceCell<SuiClient>, //Built on first use, so a dead fallback never blocks startup
+}
+
+// RPC endpoints in priority order, from SUI_RPC_URLS (comma-separated).
+// Calls go to the last endpoint that worked and fall through to the others when it fails or times out.
+pub struct RpcPool {
+    endpoints: Vec<Endpoint>,
+    preferred: AtomicUsize,
+    transport: Transport,
+}
+
+// Settings handed to SuiClientBuilder for every endpoint: per-request timeout, in-flight requests
+// per endpoint and extra headers (User-Agent included). That's all the builder takes: it creates its
+// HTTP client internally, so idle connections can't be tuned from here. Each endpoint's client lives
+// as long as the server and keeps its connections alive between requests.
+struct Transport {
+    request_timeout: Duration,
+    max_concurrent_requests: usize,
+    headers: HashMap<String, String>,
+}
+
+impl RpcPool {
+    pub fn from_config(config: &Config) -> Self {
+        Self::from_urls(&config.rpc_urls, config)
+    }
+
+    // Endpoints with deep history (an indexer-backed or archival JSON-RPC), from SUI_HISTORY_RPC_URLS.
+    // Only asked for transactions the regular endpoints no longer have.
+    pub fn history_from_config(config: &Config) -> Option<Self> {
+        if config.history_rpc_urls.is_empty() {
+            return None;
+        }
+        Some(Self::from_urls(&config.history_rpc_urls, config))
+    }
+
+    fn from_urls(urls: &[String], config: &Config) -> Self {
+        Self {
+            endpoints: urls.iter().map(|url| Endpoint::new(url)).collect(),
+            preferred: AtomicUsize::new(0),
+            transport: Transport::from_config(config),
+        }
+    }
+
+    //Run an RPC call against the healthy endpoint, failing over on transient errors. Returns the URL that answered.
+    //Not found also moves on to the next endpoint, a lagging node may not have a recent transaction yet. It's only
+    //returned when every endpoint said not found, if any of them failed we can't be sure and report that failure.
+    pub async fn call<T, F, Fut>(&self, subject: &str, f: F) -> Result<(T, String), ExplainError>
+    where
+        F: Fn(SuiClient) -> Fut,
+        Fut: Future<Output = Result<T, sui_sdk::error::Error>>,
+    {
+        let start = self.preferred.load(Ordering::Relaxed);
+        let mut last_error = None;
+        let mut not_found = None;
+
+        for offset in 0..self.endpoints.len() {
+            let index = (start + offset) % self.endpoints.len();
+            let endpoint = &self.endpoints[index];
+
+            match endpoint.run(&self.transport, subject, &f).await {
+                Ok(value) => {
+                    self.preferred.store(index, Ordering::Relaxed);
+                    return Ok((value, endpoint.url.clone()));
+                }
+                Err(e @ ExplainError::NotFound(_)) => {
+                    if offset + 1 < self.endpoints.len() {
+                        eprintln!(
+                            "{}RPC endpoint {}: {}, trying the next one",
+                            request_id::log_prefix(),
+                            endpoint.url,
+                            e
+                        );
+                    }
+                    not_found = Some(e);
+                }
+                Err(e) if e.is_transient() => {
+                    eprintln!(
+                        "{}RPC endpoint {} failed: {}",
+                        request_id::log_prefix(),
+                        endpoint.url,
+                        e
+                    );
+                    last_error = Some(e);
+                }
+                Err(e) => return Err(e),
+            }
+        }
+
+        Err(last_error
+            .or(not_found)
+            .unwrap_or_else(|| ExplainError::Connection("No RPC endpoints configured".to_string())))
+    }
+
+    //Run an RPC call against one specific endpoint of the pool, without failing over. None when the
+    //pool has no endpoint with that URL.
+    pub async fn call_at<T, F, Fut>(
+        &self,
+        url: &str,
+        subject: &str,
+        f: F,
+    ) -> Option<Result<T, ExplainError>>
+    where
+        F: FnOnce(SuiClient) -> Fut,
+        Fut: Future<Output = Result<T, sui_sdk::error::Error>>,
+    {
+        let endpoint = self.endpoints.iter().find(|endpoint| endpoint.url == url)?;
+        Some(endpoint.run(&self.transport, subject, f).await)
+    }
+}
+
+impl TxFetcher for RpcPool {
+    fn fetch<'a>(
+        &'a self,
+        digest: TransactionDigest,
+        options: &'a SuiTransactionBlockResponseOptions,
+    ) -> BoxFuture<'a, Result<(SuiTransactionBlockResponse, String), ExplainError>> {
+        async move {
+            self.call(&format!("Transaction {}", digest), |client| {
+                let options = options.clone();
+                async move {
+                    client
+                        .read_api()
+                        .get_transaction_with_options(digest, options)
+                        .await
+                }
+            })
+            .await
+        }
+        .boxed()
+    }
+}
+
+impl Endpoint {
+    fn new(url: &str) -> Self {
+        Self {
+            url: url.to_string(),
+            client: OnceCell::new(),
+        }
+    }
+
+    async fn client(&self, transport: &Transport) -> Result<SuiClient, ExplainError> {
+        self.client
+            .get_or_try_init(|| {
+                SuiClientBuilder::default()
+                    .request_timeout(transport.request_timeout)
+                    .max_concurrent_requests(transport.max_concurrent_requests)
+                    .custom_headers(transport.headers.clone())
+                    .build(&self.url)
+            })
+            .await
+            .cloned()
+            .map_err(|e| ExplainError::from_connect(e, &self.url))
+    }
+
+    //One call against this endpoint within the request timeout, errors mapped for `subject`
+    async fn run<T, F, Fut>(
+        &self,
+        transport: &Transport,
+        subject: &str,
+        f: F,
+    ) -> Result<T, ExplainError>
+    where
+        F: FnOnce(SuiClient) -> Fut,
+        Fut: Future<Output = Result<T, sui_sdk::error::Error>>,
+    {
+        let client = self.client(transport).await?;
+        match tokio::time::timeout(transport.request_timeout, f(client)).await {
+            Ok(result) => result.map_err(|e| ExplainError::from_rpc(e, subject)),
+            Err(_) => Err(ExplainError::RpcTimeout),
+        }
+    }
+}
+
+impl Transport {
+    fn from_config(config: &Config) -> Self {
+        Self {
+            request_timeout: config.rpc_request_timeout,
+            max_concurrent_requests: config.rpc_max_concurrent_requests,
+            headers: config.rpc_headers.clone(),
+        }
+    }
+}
@@ -0,0 +1,192 @@
+use futures::{FutureExt, future::BoxFuture};
+use std::{
+    collections::{BTreeMap, HashMap, HashSet},
+    str::FromStr,
+};
+use sui_json_rpc_types::SuiTransactionBlockResponse;
+use sui_types::base_types::SuiAddress;
+
+use crate::config::Config;
+use crate::enricher::ExplanationEnricher;
+use crate::models::TransactionExplanation;
+use crate::sui_client::{account_address, involved_addresses};
+
+// Objects one address has to receive, with nothing coming back, before it looks like a drain
+const DRAIN_MIN_OBJECTS: usize = 3;
+
+// Advisory safety signals for wallets. These are heuristics: a warning means "have a closer look",
+// not that the transaction is malicious, and no warning doesn't mean it's safe.
+pub trait SafetyCheck: Send + Sync {
+    //`addresses` holds every address and package the transaction involves
+    fn warnings(
+        &self,
+        explanation: &TransactionExplanation,
+        addresses: &HashSet<SuiAddress>,
+    ) -> Vec<String>;
+}
+
+// The checks every explanation goes through, run as the built-in enricher that fills warnings.
+// SAFETY_CHECKS=off turns them all off.
+pub struct SafetyChecks {
+    checks: Vec<Box<dyn SafetyCheck>>,
+}
+
+impl SafetyChecks {
+    pub fn from_config(config: &Config) -> Self {
+        if !config.safety_checks {
+            return Self { checks: vec![] };
+        }
+
+        let mut checks: Vec<Box<dyn SafetyCheck>> = vec![Box::new(ObjectDrain)];
+        if let Some(blocklist) = config.blocklist_path.as_deref().and_then(Blocklist::load) {
+            checks.push(Box::new(blocklist));
+        }
+        Self { checks }
+    }
+}
+
+impl ExplanationEnricher for SafetyChecks {
+    fn enrich<'a>(
+        &'a self,
+        explanation: &'a mut TransactionExplanation,
+        _raw: &'a SuiTransactionBlockResponse,
+    ) -> BoxFuture<'a, ()> {
+        let mut addresses = involved_addresses(explanation);
+        addresses.extend(
+            explanation
+                .links
+                .packages
+                .keys()
+                .filter_map(|id| id.parse::<SuiAddress>().ok()),
+        );
+        let warnings: Vec<String> = self
+            .checks
+            .iter()
+            .flat_map(|check| check.warnings(explanation, &addresses))
+            .collect();
+        explanation.warnings.extend(warnings);
+        async {}.boxed()
+    }
+}
+
+// Several of the sender's objects leave for one other account while nothing of value comes back:
+// the shape of a drainer dressed up as a harmless call (free mint, airdrop claim)
+struct ObjectDrain;
+
+impl SafetyCheck for ObjectDrain {
+    fn warnings(
+        &self,
+        explanation: &TransactionExplanation,
+        _addresses: &HashSet<SuiAddress>,
+    ) -> Vec<String> {
+        let Some(sender) = explanation.sender.as_deref().and_then(parse_address) else {
+            return vec![];
+        };
+
+        let receives_value = explanation.balance_changes.iter().any(|balance| {
+            !balance.is_gas
+                && balance.amount > 0
+                && account_address(&balance.owner).and_then(parse_address) == Some(sender)
+        });
+        if receives_value {
+            return vec![];
+        }
+
+        // Owned inputs always belong to the sender, so existing objects now owned by another
+        // account were handed over. Objects wrapped into other objects don't count.
+        let mut handed_over: BTreeMap<String, usize> = BTreeMap::new();
+        for change in &explanation.object_changes {
+            if change.change_type != "Transferred" && change.change_type != "Mutated" {
+                continue;
+            }
+            let Some(owner) = change.owner.as_deref() else {
+                continue;
+            };
+            match account_address(owner).and_then(parse_address) {
+                Some(recipient) if recipient != sender => {
+                    *handed_over.entry(recipient.to_string()).or_insert(0) += 1;
+                }
+                _ => {}
+            }
+        }
+
+        handed_over
+            .into_iter()
+            .filter(|(_, count)| *count >= DRAIN_MIN_OBJECTS)
+            .map(|(recipient, count)| {
+                format!(
+                    "{} of the sender's objects go to {} and nothing comes back, make sure this isn't a drainer",
+                    count, recipient
+                )
+            })
+            .collect()
+    }
+}
+
+// Addresses and packages reported as scams, loaded once at startup from the JSON object at
+// BLOCKLIST_PATH, e.g. {"0x...": "Fake airdrop claim site"}. There's no default list.
+struct Blocklist {
+    entries: HashMap<SuiAddress, String>,
+}
+
+impl Blocklist {
+    fn load(path: &str) -> Option<Self> {
+        let contents = match std::fs::read_to_string(path) {
+            Ok(contents) => contents,
+            Err(e) => {
+                eprintln!("Failed to load blocklist from {}: {}", path, e);
+                return None;
+            }
+        };
+        let raw: HashMap<String, String> = match serde_json::from_str(&contents) {
+            Ok(raw) => raw,
+            Err(e) => {
+                eprintln!("Failed to parse blocklist from {}: {}", path, e);
+                return None;
+            }
+        };
+
+        let mut entries = HashMap::new();
+        for (address, reason) in raw {
+            match SuiAddress::from_str(&address) {
+                Ok(address) => {
+                    entries.insert(address, reason);
+                }
+                Err(_) => eprintln!("Skipping blocklist entry with bad address {}", address),
+            }
+        }
+
+        println!(
+            "Loaded {} blocklisted addresses from {}",
+            entries.len(),
+            path
+        );
+        Some(Self { entries })
+    }
+}
+
+impl SafetyCheck for Blocklist {
+    fn warnings(
+        &self,
+        _explanation: &TransactionExplanation,
+        addresses: &HashSet<SuiAddress>,
+    ) -> Vec<String> {
+        let mut warnings: Vec<String> = addresses
+            .iter()
+            .filter_map(|address| {
+                let reason = self.entries.get(address)?;
+                Some(format!(
+                    "Involves {}, which is on the blocklist: {}",
+                    address, reason
+                ))
+            })
+            .collect();
+        // HashSet order isn't stable, keep the answer deterministic
+        warnings.sort();
+        warnings
+    }
+}
+
+fn parse_address(address: &str) -> Option<SuiAddress> {
+    SuiAddress::from_str(address).ok()
+}
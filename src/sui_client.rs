@@ -1,69 +1,1024 @@
-use anyhow::{Context, Result};
+use futures::StreamExt;
+use std::{
+    collections::{BTreeMap, BTreeSet, HashMap, HashSet},
+    sync::{Arc, Mutex},
+    time::Duration,
+};
 use sui_json_rpc_types::{
-    BalanceChange as SuiBalanceChange, ObjectChange, SuiTransactionBlockDataAPI,
+    BalanceChange as SuiBalanceChange, CheckpointId, ObjectChange, SuiExecutionStatus,
+    SuiObjectDataOptions, SuiTransactionBlock, SuiTransactionBlockDataAPI,
     SuiTransactionBlockEffectsAPI, SuiTransactionBlockResponse, SuiTransactionBlockResponseOptions,
+    SuiTransactionBlockResponseQuery, TransactionFilter,
+};
+use sui_types::{
+    base_types::{ObjectID, SuiAddress},
+    crypto::Signature,
+    digests::TransactionDigest,
+    error::SuiObjectResponseError,
+    gas::GasCostSummary,
+    gas_coin::GAS,
+    object::Owner,
+    signature::GenericSignature,
+    transaction::{
+        Argument, CallArg, Command, SenderSignedData, TransactionData, TransactionDataAPI,
+        TransactionKind,
+    },
+};
+use tokio::time::Instant;
+
+use crate::abort_codes::AbortCodes;
+use crate::address_labels::AddressLabels;
+use crate::cache::{Enrichments, ExplanationCache};
+use crate::commands;
+use crate::config::Config;
+use crate::diff::diff_explanations;
+use crate::enricher::ExplanationEnricher;
+use crate::error::ExplainError;
+use crate::format;
+use crate::models::{
+    ActivityItem, BalanceChange, BatchStats, CoinFlow, ContextTransaction, EventInfo,
+    ExplainOptions, GasBreakdown, MoveCall, NetAmount, ObjectMod as ModelObjectChange,
+    OwnerChanges, ResponseMeta, SenderBalance, TransactionDiff, TransactionExplanation,
+    TransactionStatus,
 };
-use sui_sdk::SuiClientBuilder;
+use crate::pure_args;
+use crate::request_id;
+use crate::rpc::{RpcPool, TxFetcher};
+use crate::safety::SafetyChecks;
+use crate::system_objects;
+
+pub const NETWORK: &str = "mainnet";
+
+// Base58 of 32 bytes is 43 or 44 characters
+const MAX_DIGEST_LEN: usize = 44;
+
+// How long `wait` keeps polling for a transaction that isn't indexed yet
+const DEFAULT_WAIT_MS: u64 = 10_000;
+const MAX_WAIT_MS: u64 = 30_000;
+const POLL_INTERVAL: Duration = Duration::from_millis(500);
+
+// simplify_type labels kept in memory, airdrops repeat one type hundreds of times
+const MAX_CACHED_TYPE_LABELS: usize = 10_000;
 
-use crate::models::{BalanceChange, ObjectMod as ModelObjectChange, TransactionExplanation};
+// The validator set only changes at epoch boundaries, an hour-old copy of the names is fine
+const VALIDATOR_NAMES_TTL: Duration = Duration::from_secs(3600);
+
+// Neighbouring transactions include_context returns on each side
+const DEFAULT_CONTEXT_SIZE: usize = 3;
+const MAX_CONTEXT_SIZE: usize = 10;
+
+// Address history: transactions returned by default and at most, and how far back paging goes
+const DEFAULT_HISTORY_LIMIT: usize = 20;
+const MAX_HISTORY_LIMIT: usize = 100;
+const HISTORY_PAGE_SIZE: usize = 50;
+const MAX_HISTORY_PAGES: usize = 20;
+
+// Display lookups in flight at once for a single transaction, mints can create hundreds of objects
+const DISPLAY_CONCURRENCY: usize = 8;
 
 pub struct SuiClient {
-    client: sui_sdk::SuiClient,
+    config: Arc<Config>,
+    rpc: Arc<RpcPool>,
+    fetcher: Arc<dyn TxFetcher>, //Transaction lookups, the RPC pool unless one was injected
+    history: Option<RpcPool>, //Deep-history endpoints for pruned transactions (SUI_HISTORY_RPC_URLS)
+    abort_codes: AbortCodes,
+    address_labels: AddressLabels,
+    enrichers: Vec<Box<dyn ExplanationEnricher>>, //Run after every parse, safety checks first
+    display_types: Mutex<HashMap<String, bool>>,  //Full object type -> whether it has a Display
+    type_labels: Mutex<HashMap<String, String>>,  //Full type -> simplify_type label
+    coin_decimals: Mutex<HashMap<String, Option<u8>>>, //Full coin type -> decimals, None without metadata
+    validators: Mutex<Option<(Instant, HashMap<String, String>)>>, //Validator address -> name, when loaded
+    cache: ExplanationCache,
 }
 
 impl SuiClient {
-    //Create a new Sui client connected to mainnet
-    pub async fn new() -> Result<Self> {
-        //Connect to Sui mainnet RPC endpoint
-        let client = SuiClientBuilder::default()
-            .build("https://fullnode.mainnet.sui.io:443")
-            .await
-            .context("Failed to build Sui client")?;
+    //Create a new Sui client, connections to the RPC endpoints are made on first use
+    pub fn new(config: Arc<Config>) -> Self {
+        let rpc = Arc::new(RpcPool::from_config(&config));
+        Self::with_fetcher(config, rpc.clone(), rpc)
+    }
+
+    //Same as new, but transactions are looked up through `fetcher` instead of the RPC pool
+    pub fn with_fetcher(
+        config: Arc<Config>,
+        rpc: Arc<RpcPool>,
+        fetcher: Arc<dyn TxFetcher>,
+    ) -> Self {
+        Self {
+            rpc,
+            fetcher,
+            history: RpcPool::history_from_config(&config),
+            abort_codes: AbortCodes::new(config.abort_codes_path.as_deref()),
+            address_labels: AddressLabels::new(config.address_labels_path.as_deref()),
+            // Only the safety checks, see ExplanationEnricher for why the rest stays in the parse
+            enrichers: vec![Box::new(SafetyChecks::from_config(&config))],
+            display_types: Mutex::new(HashMap::new()),
+            type_labels: Mutex::new(HashMap::new()),
+            coin_decimals: Mutex::new(HashMap::new()),
+            validators: Mutex::new(None),
+            cache: ExplanationCache::new(config.cache_core_ttl, config.cache_enrichment_ttl),
+            config,
+        }
+    }
 
-        Ok(Self { client })
+    //The settings the server was started with
+    pub fn config(&self) -> &Config {
+        &self.config
+    }
+
+    //Add an enricher that runs after the built-in ones on every explanation
+    pub fn with_enricher(mut self, enricher: impl ExplanationEnricher + 'static) -> Self {
+        self.enrichers.push(Box::new(enricher));
+        self
     }
 
     //Fetch and explain a transaction by its digest (hash)
-    pub async fn explain_transaction(&self, digest: &str) -> Result<TransactionExplanation> {
+    pub async fn explain_transaction(
+        &self,
+        digest: &str,
+        options: &ExplainOptions,
+    ) -> Result<(TransactionExplanation, ResponseMeta), ExplainError> {
         //Parse the digest string into a proper type
-        let tx_digest = digest
+        let tx_digest = self.parse_digest(digest)?;
+
+        // Finalized transactions never change, so the parsed explanation is reused as is
+        let core_key = core_cache_key(digest, options);
+        let mut cached = self.cache.core(&core_key);
+        let flight = match cached {
+            Some(_) => None,
+            None => {
+                // An identical request may be fetching this right now, share its result
+                let flight = self.cache.single_flight(&core_key).await;
+                cached = self.cache.core(&core_key);
+                Some(flight)
+            }
+        };
+        let (mut explanation, mut meta) = match cached {
+            Some((explanation, meta)) => (
+                explanation,
+                ResponseMeta {
+                    cached: true,
+                    ..meta
+                },
+            ),
+            None => {
+                //Fetch the transaction with all details, optionally waiting for it to be indexed
+                let (tx_response, meta) =
+                    self.fetch_for_options(tx_digest, digest, options).await?;
+                if mentions_staking(&tx_response) {
+                    self.load_validator_names().await;
+                }
+                let decimals_complete = self.load_coin_decimals(&tx_response).await;
+
+                let explanation = self.parse_and_enrich(digest, &tx_response, options).await?;
+                // Only finalized transactions are kept: one served before its checkpoint would stay
+                // that way. Raw fallback amounts from a failed lookup would stick for as long as the
+                // entry lives too, the next request gets another chance at the metadata instead.
+                if decimals_complete && explanation.checkpoint.is_some() {
+                    self.cache
+                        .store_core(&core_key, (explanation.clone(), meta.clone()));
+                }
+                (explanation, meta)
+            }
+        };
+        drop(flight);
+
+        // Enrichments reflect the current chain state, so they're cached separately and expire
+        if options.resolve_display
+            || options.include_sender_balance
+            || options.include_context
+            || options.include_checkpoint_position
+        {
+            let key = enrichment_cache_key(&core_key, options);
+            let enrichments = match self.cache.enrichments(&key) {
+                Some(enrichments) => enrichments,
+                None => {
+                    let _flight = self.cache.single_flight(&key).await;
+                    match self.cache.enrichments(&key) {
+                        Some(enrichments) => enrichments,
+                        None => {
+                            let enrichments = self.enrich(tx_digest, &explanation, options).await;
+                            self.cache.store_enrichments(&key, enrichments.clone());
+                            enrichments
+                        }
+                    }
+                }
+            };
+            enrichments.apply(&mut explanation);
+        }
+
+        if !options.watchlist.is_empty() {
+            explanation.matched_addresses =
+                self.matched_addresses(&explanation, &options.watchlist);
+        }
+
+        if options.group_by_owner {
+            explanation.by_owner = group_by_owner(&explanation);
+        }
+
+        // Filtered last, so the summary, watchlist matches and by_owner still see every change
+        if !options.object_type_filter.is_empty() {
+            let filter: Vec<String> = options
+                .object_type_filter
+                .iter()
+                .map(|f| f.to_lowercase())
+                .collect();
+            explanation.object_changes.retain(|change| {
+                let label = self.simplify_type(&change.full_type).to_lowercase();
+                filter.iter().any(|f| label.contains(f.as_str()))
+            });
+        }
+
+        // Asked fresh on every request, cached or not, since it's about the node as it is now
+        if options.check_node_lag {
+            self.check_node_lag(&mut meta).await;
+        }
+
+        Ok((explanation, meta))
+    }
+
+    //Net gain or loss per coin for one address across several transactions, gas included.
+    //Fails as a whole if any transaction can't be explained, a partial total would mislead.
+    pub async fn net_balances(
+        &self,
+        address: &str,
+        digests: &[String],
+        options: &ExplainOptions,
+    ) -> Result<Vec<NetAmount>, ExplainError> {
+        let address: SuiAddress = address
             .parse()
-            .context("Invalid transaction digest format")?;
+            .map_err(|_| ExplainError::InvalidAddress(address.to_string()))?;
+
+        let results: Vec<_> = futures::stream::iter(digests)
+            .map(|digest| self.explain_transaction(digest, options))
+            .buffer_unordered(self.config.batch_concurrency)
+            .collect()
+            .await;
+
+        // Full coin type -> (label, running total)
+        let mut net: BTreeMap<String, (String, i128)> = BTreeMap::new();
+        for result in results {
+            let (explanation, _) = result?;
+            for balance in &explanation.balance_changes {
+                let owner =
+                    account_address(&balance.owner).and_then(|a| a.parse::<SuiAddress>().ok());
+                if owner != Some(address) {
+                    continue;
+                }
+                net.entry(balance.full_type.clone())
+                    .or_insert_with(|| (balance.coin_type.clone(), 0))
+                    .1 += balance.amount;
+            }
+        }
+
+        Ok(net
+            .into_iter()
+            .filter(|(_, (_, amount))| *amount != 0)
+            .map(|(coin_type, (coin, amount))| NetAmount {
+                amount_readable: self.readable_amount(&coin_type, amount, options).0,
+                coin,
+                amount,
+            })
+            .collect())
+    }
+
+    //Gas, outcomes, categories and how much of each coin changed hands across several transactions.
+    //Digests that can't be explained are counted as errors rather than failing the whole rollup.
+    pub async fn batch_stats(&self, digests: &[String], options: &ExplainOptions) -> BatchStats {
+        // BATCH_CONCURRENCY at a time like the batch endpoint, the totals don't depend on the order
+        let results: Vec<_> = futures::stream::iter(digests)
+            .map(|digest| self.explain_transaction(digest, options))
+            .buffer_unordered(self.config.batch_concurrency)
+            .collect()
+            .await;
+
+        let mut stats = BatchStats {
+            transaction_count: digests.len(),
+            ..Default::default()
+        };
+        // Full coin type -> (label, amount received)
+        let mut moved: BTreeMap<String, (String, i128)> = BTreeMap::new();
+        for result in results {
+            let Ok((explanation, _)) = result else {
+                stats.errors += 1;
+                continue;
+            };
+
+            if explanation.status == "Success" {
+                stats.succeeded += 1;
+            } else {
+                stats.failed += 1;
+            }
+            stats.total_gas_used += explanation.gas_used;
+            *stats
+                .categories
+                .entry(explanation.category.unwrap_or_else(|| "Other".to_string()))
+                .or_insert(0) += 1;
+
+            // Counting only the receiving side, so a transfer isn't counted twice
+            for balance in &explanation.balance_changes {
+                if balance.is_gas || balance.amount <= 0 {
+                    continue;
+                }
+                moved
+                    .entry(balance.full_type.clone())
+                    .or_insert_with(|| (balance.coin_type.clone(), 0))
+                    .1 += balance.amount;
+            }
+        }
+
+        stats.total_gas_used_sui = format::mist_to_sui_string(
+            stats.total_gas_used as i128,
+            false,
+            &self.sui_style(options),
+        );
+        stats.value_moved = moved
+            .into_iter()
+            .map(|(coin_type, (coin, amount))| NetAmount {
+                amount_readable: self.readable_amount(&coin_type, amount, options).0,
+                coin,
+                amount,
+            })
+            .collect();
+        stats
+    }
+
+    //Execute transaction data against the current state without committing it, and explain the outcome
+    //like a real transaction. Execution failures still come back as an explanation with its gas.
+    pub async fn dry_run(
+        &self,
+        tx_data: TransactionData,
+        options: &ExplainOptions,
+    ) -> Result<(TransactionExplanation, GasBreakdown), ExplainError> {
+        let digest = tx_data.digest();
+        let (dry_run, _) = self
+            .rpc
+            .call(&format!("Transaction {}", digest), |client| {
+                let tx_data = tx_data.clone();
+                async move { client.read_api().dry_run_transaction_block(tx_data).await }
+            })
+            .await?;
+
+        let gas = dry_run.effects.gas_cost_summary();
+        let (gas_used, net_rebate) = net_gas(gas);
+        let gas_price = tx_data.gas_price();
+        let breakdown = GasBreakdown {
+            gas_price,
+            gas_units: (gas_price > 0).then(|| gas_used / gas_price),
+            computation_cost: gas.computation_cost,
+            storage_cost: gas.storage_cost,
+            storage_rebate: gas.storage_rebate,
+            non_refundable_storage_fee: gas.non_refundable_storage_fee,
+            gas_used,
+            net_rebate,
+            gas_used_sui: format::mist_to_sui_string(
+                gas_used as i128,
+                false,
+                &self.sui_style(options),
+            ),
+        };
+
+        // Same shape as a fetched transaction, so it goes through the regular parsing
+        let response = SuiTransactionBlockResponse {
+            transaction: Some(SuiTransactionBlock {
+                data: dry_run.input,
+                tx_signatures: vec![],
+            }),
+            effects: Some(dry_run.effects),
+            events: Some(dry_run.events),
+            object_changes: Some(dry_run.object_changes),
+            balance_changes: Some(dry_run.balance_changes),
+            ..SuiTransactionBlockResponse::new(digest)
+        };
+        if mentions_staking(&response) {
+            self.load_validator_names().await;
+        }
+        self.load_coin_decimals(&response).await;
+        let mut explanation = self
+            .parse_and_enrich(&digest.to_string(), &response, options)
+            .await?;
+        if options.group_by_owner {
+            explanation.by_owner = group_by_owner(&explanation);
+        }
+
+        // Filtered last, so the summary, watchlist matches and by_owner still see every change
+        if !options.object_type_filter.is_empty() {
+            let filter: Vec<String> = options
+                .object_type_filter
+                .iter()
+                .map(|f| f.to_lowercase())
+                .collect();
+            explanation.object_changes.retain(|change| {
+                let label = self.simplify_type(&change.full_type).to_lowercase();
+                filter.iter().any(|f| label.contains(f.as_str()))
+            });
+        }
+
+        Ok((explanation, breakdown))
+    }
+
+    //What a transaction actually did (left) next to what a dry run of its original bytes predicts (right).
+    //The dry run executes against the current state, so besides misprediction the diff shows how the
+    //chain has moved on. Inputs consumed by the real execution usually make the dry run fail outright.
+    pub async fn diff_against_dry_run(
+        &self,
+        digest: &str,
+        options: &ExplainOptions,
+    ) -> Result<TransactionDiff, ExplainError> {
+        let tx_digest = self.parse_digest(digest)?;
+        let (actual, _) = self.explain_transaction(digest, options).await?;
+
+        let raw_options = SuiTransactionBlockResponseOptions {
+            show_raw_input: true,
+            ..Default::default()
+        };
+        let (response, _) = self.fetch_transaction(tx_digest, &raw_options).await?;
+        // Pruned or indexer-backed nodes can answer without the raw bytes
+        let signed = bcs::from_bytes::<SenderSignedData>(&response.raw_transaction)
+            .map_err(|_| ExplainError::BytesUnavailable(digest.to_string()))?;
+
+        let (predicted, _) = self
+            .dry_run(signed.transaction_data().clone(), options)
+            .await?;
+        Ok(diff_explanations(&actual, &predicted))
+    }
+
+    //Fill in decoded_args on each call by looking up the called function's parameter types, so pure
+    //inputs read as "42" or "0xab.." instead of "Input(0)". Calls we can't resolve are left alone.
+    //With a function (see calls_function) only the calls to it are decoded, nothing else is fetched.
+    //`calls` are the transaction's Move calls from the `first`-th on, so a page can be decoded alone.
+    pub async fn decode_call_args(
+        &self,
+        tx_data: &TransactionData,
+        calls: &mut [MoveCall],
+        first: usize,
+        function: Option<&str>,
+    ) {
+        let TransactionKind::ProgrammableTransaction(ptb) = tx_data.kind() else {
+            return;
+        };
+        let commands = ptb
+            .commands
+            .iter()
+            .filter_map(|command| match command {
+                Command::MoveCall(call) => Some(call),
+                _ => None,
+            })
+            .skip(first);
+
+        // One lookup per package, a PTB often calls the same one several times
+        let mut packages = HashMap::new();
+        for (command, call) in commands.zip(calls.iter_mut()) {
+            if function.is_some_and(|function| !calls_function(call, function)) {
+                continue;
+            }
+            let package = command.package;
+            if !packages.contains_key(&package) {
+                let modules = self
+                    .rpc
+                    .call(&format!("Package {}", package), |client| async move {
+                        client
+                            .read_api()
+                            .get_normalized_move_modules_by_package(package)
+                            .await
+                    })
+                    .await
+                    .map(|(modules, _)| modules);
+                if let Err(e) = &modules {
+                    eprintln!(
+                        "{}Could not load package {}: {}",
+                        request_id::log_prefix(),
+                        package,
+                        e
+                    );
+                }
+                packages.insert(package, modules.ok());
+            }
+
+            let Some(function) = packages[&package]
+                .as_ref()
+                .and_then(|modules| modules.get(&call.module))
+                .and_then(|module| module.exposed_functions.get(&call.function))
+            else {
+                continue;
+            };
+
+            let decoded = command
+                .arguments
+                .iter()
+                .zip(&call.args)
+                .zip(&function.parameters)
+                .map(|((argument, shown), parameter)| match argument {
+                    Argument::Input(i) => match ptb.inputs.get(*i as usize) {
+                        Some(CallArg::Pure(bytes)) => {
+                            match pure_args::normalized_type_name(parameter, &call.type_args) {
+                                Some(type_name) => pure_args::decode_pure_arg(bytes, &type_name),
+                                None => shown.clone(),
+                            }
+                        }
+                        Some(CallArg::Object(object)) => {
+                            self.shorten_address(&object.id().to_string())
+                        }
+                        _ => shown.clone(),
+                    },
+                    _ => shown.clone(),
+                })
+                .collect();
+            call.decoded_args = Some(decoded);
+        }
+    }
+
+    //Fetch just the effects and report whether the transaction succeeded and what it cost
+    pub async fn transaction_status(
+        &self,
+        digest: &str,
+        options: &ExplainOptions,
+    ) -> Result<TransactionStatus, ExplainError> {
+        let options = ExplainOptions {
+            status_only: true,
+            ..options.clone()
+        };
+        let (explanation, _) = self.explain_transaction(digest, &options).await?;
 
-        //Fetch the transaction with all details
-        let tx_response = self
-            .client
-            .read_api()
-            .get_transaction_with_options(
+        Ok(TransactionStatus {
+            digest: explanation.digest,
+            status: explanation.status,
+            gas_used_sui: explanation.gas_used_sui,
+        })
+    }
+
+    //Fetch only the transaction input and decode its Move calls. With a function ("module::function",
+    //optionally package-qualified) only the calls to it are kept, with their arguments decoded.
+    //A function that wasn't called gives an empty list. `offset` and `limit` page through the calls
+    //before anything is decoded, the count that comes back is every Move call in the transaction.
+    pub async fn move_calls(
+        &self,
+        digest: &str,
+        function: Option<&str>,
+        offset: usize,
+        limit: usize,
+    ) -> Result<(Vec<MoveCall>, usize), ExplainError> {
+        let tx_digest = self.parse_digest(digest)?;
+        let (tx, _) = self
+            .fetch_transaction(
                 tx_digest,
-                SuiTransactionBlockResponseOptions {
+                &SuiTransactionBlockResponseOptions {
                     show_input: true,
-                    show_effects: true,
-                    show_events: true,
-                    show_object_changes: true,
-                    show_balance_changes: true,
+                    show_raw_input: function.is_some(),
                     ..Default::default()
                 },
             )
+            .await?;
+
+        let mut calls = tx
+            .transaction
+            .as_ref()
+            .and_then(|t| commands::programmable(t.data.transaction()))
+            .map(|ptb| commands::move_calls(&ptb.commands))
+            .unwrap_or_default();
+        let total = calls.len();
+        let mut calls: Vec<MoveCall> = calls.into_iter().skip(offset).take(limit).collect();
+
+        let Some(function) = function else {
+            return Ok((calls, total));
+        };
+        // Decoding needs the BCS form of the inputs, which only the raw transaction has
+        match bcs::from_bytes::<SenderSignedData>(&tx.raw_transaction) {
+            Ok(signed) => {
+                self.decode_call_args(
+                    signed.transaction_data(),
+                    &mut calls,
+                    offset,
+                    Some(function),
+                )
+                .await
+            }
+            Err(_) => eprintln!(
+                "{}No raw input for {}, arguments left undecoded",
+                request_id::log_prefix(),
+                digest
+            ),
+        }
+        // Only after decoding, which pairs calls with the transaction's commands by position
+        calls.retain(|call| calls_function(call, function));
+
+        Ok((calls, total))
+    }
+
+    //Transactions sent by an address, newest first, optionally only those between from_ms and to_ms.
+    //Paging stops at the first transaction older than from_ms, after MAX_HISTORY_PAGES pages, or
+    //once the limit is reached.
+    pub async fn address_history(
+        &self,
+        address: &str,
+        limit: Option<usize>,
+        from_ms: Option<u64>,
+        to_ms: Option<u64>,
+    ) -> Result<Vec<ContextTransaction>, ExplainError> {
+        let sender: SuiAddress = address
+            .parse()
+            .map_err(|_| ExplainError::InvalidAddress(address.to_string()))?;
+        let limit = limit
+            .unwrap_or(DEFAULT_HISTORY_LIMIT)
+            .min(MAX_HISTORY_LIMIT);
+        // The walk below only checks the limit after taking a transaction
+        if limit == 0 {
+            return Ok(vec![]);
+        }
+        let options = ExplainOptions::default();
+        let show = SuiTransactionBlockResponseOptions {
+            show_input: true,
+            show_effects: true,
+            show_object_changes: true,
+            show_balance_changes: true,
+            ..Default::default()
+        };
+
+        let mut transactions = vec![];
+        let filter = TransactionFilter::FromAddress(sender);
+        let subject = format!("Transactions of {}", address);
+        self.walk_transactions(&subject, filter, show, from_ms, to_ms, |tx| {
+            transactions.push(tx.clone());
+            transactions.len() < limit
+        })
+        .await?;
+
+        let mut history = vec![];
+        for tx in &transactions {
+            if let Some(entry) = self.context_entry(tx, &options).await {
+                history.push(entry);
+            }
+        }
+        Ok(history)
+    }
+
+    //Digests of every transaction an address sent or received between from_ms and to_ms, for totals
+    //over a period. A range holding more than `limit` of them, or too long to page through, is an
+    //error: a total over part of it would mislead.
+    pub async fn address_digests_in_range(
+        &self,
+        address: &str,
+        from_ms: Option<u64>,
+        to_ms: Option<u64>,
+        limit: usize,
+    ) -> Result<Vec<String>, ExplainError> {
+        let account: SuiAddress = address
+            .parse()
+            .map_err(|_| ExplainError::InvalidAddress(address.to_string()))?;
+
+        // Sent and received are separate queries, a transfer to yourself shows up in both
+        let mut digests = BTreeSet::new();
+        let subject = format!("Transactions of {}", address);
+        for filter in [
+            TransactionFilter::FromAddress(account),
+            TransactionFilter::ToAddress(account),
+        ] {
+            let show = SuiTransactionBlockResponseOptions::default();
+            let complete = self
+                .walk_transactions(&subject, filter, show, from_ms, to_ms, |tx| {
+                    digests.insert(tx.digest.to_string());
+                    digests.len() <= limit
+                })
+                .await?;
+            if !complete || digests.len() > limit {
+                return Err(ExplainError::InvalidTimeRange(format!(
+                    "more than {} transactions in range, narrow it or list the digests",
+                    limit.min(digests.len())
+                )));
+            }
+        }
+
+        Ok(digests.into_iter().collect())
+    }
+
+    //Page through the transactions matching `filter`, newest first, handing each one between from_ms
+    //and to_ms to `visit` until it returns false. Without a timestamp a transaction can't be placed
+    //in a bounded range and is skipped. True when the walk got to the start of the range or of the
+    //history, false when `visit` or MAX_HISTORY_PAGES cut it short.
+    async fn walk_transactions(
+        &self,
+        subject: &str,
+        filter: TransactionFilter,
+        show: SuiTransactionBlockResponseOptions,
+        from_ms: Option<u64>,
+        to_ms: Option<u64>,
+        mut visit: impl FnMut(&SuiTransactionBlockResponse) -> bool,
+    ) -> Result<bool, ExplainError> {
+        if let (Some(from), Some(to)) = (from_ms, to_ms) {
+            if from > to {
+                return Err(ExplainError::InvalidTimeRange(format!(
+                    "from_ms {} is after to_ms {}",
+                    from, to
+                )));
+            }
+        }
+
+        let query = SuiTransactionBlockResponseQuery::new(Some(filter), Some(show));
+        let mut cursor = None;
+        for _ in 0..MAX_HISTORY_PAGES {
+            let (page, _) = self
+                .rpc
+                .call(subject, |client| {
+                    let query = query.clone();
+                    async move {
+                        client
+                            .read_api()
+                            .query_transaction_blocks(query, cursor, Some(HISTORY_PAGE_SIZE), true)
+                            .await
+                    }
+                })
+                .await?;
+
+            for tx in &page.data {
+                if from_ms.is_some() || to_ms.is_some() {
+                    let Some(timestamp) = tx.timestamp_ms else {
+                        continue;
+                    };
+                    if from_ms.is_some_and(|from| timestamp < from) {
+                        return Ok(true);
+                    }
+                    if to_ms.is_some_and(|to| timestamp > to) {
+                        continue;
+                    }
+                }
+                if !visit(tx) {
+                    return Ok(false);
+                }
+            }
+
+            if !page.has_next_page {
+                return Ok(true);
+            }
+            cursor = page.next_cursor;
+        }
+
+        Ok(false)
+    }
+
+    //Find the transaction that last touched an object. Deleted objects no longer report it,
+    //so fall back to the most recent transaction that changed them.
+    pub async fn last_transaction_for_object(
+        &self,
+        object_id: &str,
+    ) -> Result<String, ExplainError> {
+        let id: ObjectID = object_id
+            .parse()
+            .map_err(|_| ExplainError::InvalidObjectId(object_id.to_string()))?;
+
+        let (response, _) = self
+            .rpc
+            .call(&format!("Object {}", object_id), |client| async move {
+                client
+                    .read_api()
+                    .get_object_with_options(
+                        id,
+                        SuiObjectDataOptions {
+                            show_previous_transaction: true,
+                            ..Default::default()
+                        },
+                    )
+                    .await
+            })
+            .await?;
+
+        if let Some(digest) = response.data.and_then(|d| d.previous_transaction) {
+            return Ok(digest.to_string());
+        }
+
+        match response.error {
+            Some(SuiObjectResponseError::Deleted { .. }) => {
+                let (page, _) = self
+                    .rpc
+                    .call(
+                        &format!("Transactions of object {}", object_id),
+                        |client| async move {
+                            client
+                                .read_api()
+                                .query_transaction_blocks(
+                                    SuiTransactionBlockResponseQuery::new_with_filter(
+                                        TransactionFilter::ChangedObject(id),
+                                    ),
+                                    None,
+                                    Some(1),
+                                    true,
+                                )
+                                .await
+                        },
+                    )
+                    .await?;
+
+                page.data
+                    .first()
+                    .map(|tx| tx.digest.to_string())
+                    .ok_or_else(|| ExplainError::ObjectNotFound(object_id.to_string()))
+            }
+            _ => Err(ExplainError::ObjectNotFound(object_id.to_string())),
+        }
+    }
+
+    fn parse_digest(&self, digest: &str) -> Result<TransactionDigest, ExplainError> {
+        // Don't parse, or echo back, something that can't possibly be a digest
+        if digest.len() > MAX_DIGEST_LEN {
+            return Err(ExplainError::InvalidDigest(format!(
+                "{} characters, a digest has at most {}",
+                digest.len(),
+                MAX_DIGEST_LEN
+            )));
+        }
+
+        digest
+            .parse()
+            .map_err(|_| ExplainError::InvalidDigest(digest.to_string()))
+    }
+
+    async fn fetch_for_options(
+        &self,
+        tx_digest: TransactionDigest,
+        digest: &str,
+        options: &ExplainOptions,
+    ) -> Result<(SuiTransactionBlockResponse, ResponseMeta), ExplainError> {
+        // Status-only queries skip everything but effects, so there's nothing else to parse
+        let response_options = if options.status_only {
+            SuiTransactionBlockResponseOptions {
+                show_effects: true,
+                ..Default::default()
+            }
+        } else {
+            SuiTransactionBlockResponseOptions {
+                show_input: true,
+                show_effects: true,
+                show_events: true,
+                show_object_changes: true,
+                show_balance_changes: true,
+                ..Default::default()
+            }
+        };
+
+        if options.wait {
+            self.wait_for_transaction(
+                tx_digest,
+                digest,
+                &response_options,
+                options.wait_timeout_ms,
+            )
             .await
-            .context("Failed to fetch transaction from Sui")?;
+        } else {
+            self.fetch_transaction(tx_digest, &response_options).await
+        }
+    }
+
+    async fn fetch_transaction(
+        &self,
+        tx_digest: TransactionDigest,
+        response_options: &SuiTransactionBlockResponseOptions,
+    ) -> Result<(SuiTransactionBlockResponse, ResponseMeta), ExplainError> {
+        let not_found = match self.fetcher.fetch(tx_digest, response_options).await {
+            Ok((response, rpc_endpoint)) => {
+                let meta = ResponseMeta {
+                    rpc_endpoint,
+                    source: "fullnode".to_string(),
+                    cached: false,
+                    node_latest_checkpoint: None,
+                    node_lag_ms: None,
+                    node_behind: false,
+                };
+                return Ok((response, meta));
+            }
+            Err(e @ ExplainError::NotFound(_)) => e,
+            Err(e) => return Err(e),
+        };
+
+        // Full nodes prune old transactions, a history endpoint may still have them
+        let Some(history) = &self.history else {
+            return Err(not_found);
+        };
+        let (response, rpc_endpoint) = history.fetch(tx_digest, response_options).await?;
+        let meta = ResponseMeta {
+            rpc_endpoint,
+            source: "history".to_string(),
+            cached: false,
+            node_latest_checkpoint: None,
+            node_lag_ms: None,
+            node_behind: false,
+        };
+        Ok((response, meta))
+    }
+
+    //Poll until a freshly submitted transaction shows up, or report it as pending once the timeout runs out
+    async fn wait_for_transaction(
+        &self,
+        tx_digest: TransactionDigest,
+        digest: &str,
+        response_options: &SuiTransactionBlockResponseOptions,
+        timeout_ms: Option<u64>,
+    ) -> Result<(SuiTransactionBlockResponse, ResponseMeta), ExplainError> {
+        let timeout = timeout_ms.unwrap_or(DEFAULT_WAIT_MS).min(MAX_WAIT_MS);
+        let deadline = Instant::now() + Duration::from_millis(timeout);
+
+        loop {
+            match self.fetch_transaction(tx_digest, response_options).await {
+                Err(ExplainError::NotFound(_)) if Instant::now() < deadline => {
+                    tokio::time::sleep(POLL_INTERVAL).await;
+                }
+                Err(ExplainError::NotFound(_)) => {
+                    return Err(ExplainError::Pending(digest.to_string()));
+                }
+                other => return other,
+            }
+        }
+    }
+
+    //Who signed. Signatures come in order sender then sponsor; a multisig is expanded into the
+    //members whose keys took part, anything else is credited to the account it signs for.
+    fn signers(&self, tx_data: &SuiTransactionBlock) -> (Vec<String>, Option<u16>) {
+        let accounts = [*tx_data.data.sender(), tx_data.data.gas_data().owner];
+        let mut signers = vec![];
+        let mut threshold = None;
+
+        for (i, signature) in tx_data.tx_signatures.iter().enumerate() {
+            match signature {
+                GenericSignature::MultiSig(multisig) => {
+                    let public_key = multisig.get_pk();
+                    threshold = Some(*public_key.threshold());
+                    for index in multisig.get_indices().unwrap_or_default() {
+                        if let Some((key, _)) = public_key.pubkeys().get(index as usize) {
+                            signers.push(SuiAddress::from(key).to_string());
+                        }
+                    }
+                }
+                _ => {
+                    if let Some(account) = accounts.get(i) {
+                        signers.push(account.to_string());
+                    }
+                }
+            }
+        }
 
-        self.parse_transaction(digest, tx_response)
+        (signers, threshold)
     }
 
-    // Convert the raw Sui response into our human-readable format
-    fn parse_transaction(
+    // Convert the raw Sui response into our human-readable format. Nothing is fetched, the response
+    // is explained with whatever names and decimals the client already knows.
+    pub fn parse_transaction(
         &self,
         digest: &str,
-        tx: SuiTransactionBlockResponse,
-    ) -> Result<TransactionExplanation> {
+        tx: &SuiTransactionBlockResponse,
+        options: &ExplainOptions,
+    ) -> Result<TransactionExplanation, ExplainError> {
+        // Pruned nodes can keep effects but drop the transaction itself
+        let data_requested = !options.status_only;
+        if data_requested && tx.transaction.is_none() && tx.effects.is_none() {
+            return Err(ExplainError::DataPruned(digest.to_string()));
+        }
+
         let mut explanation = TransactionExplanation {
             digest: digest.to_string(),
+            data_available: tx.transaction.is_some() || !data_requested,
+            checkpoint: tx.checkpoint,
             ..Default::default()
         };
 
+        // An empty list only means "none" when the node actually returned that section
+        let sections = [
+            ("input", tx.transaction.is_some()),
+            ("effects", tx.effects.is_some()),
+            ("events", tx.events.is_some()),
+            ("object_changes", tx.object_changes.is_some()),
+            ("balance_changes", tx.balance_changes.is_some()),
+        ];
+        explanation.available_sections = sections
+            .iter()
+            .filter(|(_, available)| *available)
+            .map(|(name, _)| name.to_string())
+            .collect();
+
+        let mut upgraded_from = None;
+        let mut interacted: BTreeSet<ObjectID> = BTreeSet::new();
+        explanation.kind = "Unknown".to_string();
         if let Some(tx_data) = &tx.transaction {
-            explanation.sender = tx_data.data.sender().to_string();
+            explanation.sender = Some(tx_data.data.sender().to_string());
+            explanation.kind = tx_data.data.transaction().name().to_string();
+            (explanation.signers, explanation.threshold) = self.signers(tx_data);
+            explanation.signature_schemes = tx_data
+                .tx_signatures
+                .iter()
+                .map(|signature| signature_scheme(signature).to_string())
+                .collect();
+
+            let gas_owner = tx_data.data.gas_data().owner;
+            if gas_owner != *tx_data.data.sender() {
+                explanation.sponsor = Some(gas_owner.to_string());
+            }
+
+            if let Some(ptb) = commands::programmable(tx_data.data.transaction()) {
+                explanation.command_summary = commands::command_summary(&ptb.commands);
+                explanation.shared_objects = commands::shared_inputs(ptb)
+                    .iter()
+                    .map(|id| self.shorten_address(&id.to_string()))
+                    .collect();
+                upgraded_from = commands::upgraded_package(&ptb.commands);
+                interacted.extend(commands::called_packages(&ptb.commands));
+            }
         }
 
         if let Some(effects) = &tx.effects {
@@ -74,81 +1029,730 @@ impl SuiClient {
                 format!("Failed : {:?}", effects.status())
             };
 
+            // Move aborts only carry a number, look up what it means
+            if let SuiExecutionStatus::Failure { error } = effects.status() {
+                explanation.failure_reason = self.abort_codes.describe(error);
+            }
+
             // Calculate total gas used
             let gas_used = effects.gas_cost_summary();
-            explanation.gas_used =
-                gas_used.computation_cost + gas_used.storage_cost - gas_used.storage_rebate;
+            explanation.gas_used = net_gas(gas_used).0;
 
             // Convert MIST to SUI (1 SUI = 1,000,000,000 MIST)
-            let sui_amount = explanation.gas_used as f64 / 1_000_000_000.0;
-            explanation.gas_used_sui = format!("{:.6} SUI", sui_amount);
+            explanation.gas_used_sui = format::mist_to_sui_string(
+                explanation.gas_used as i128,
+                false,
+                &self.sui_style(options),
+            );
+
+            // Effects only report the cost in MIST, so units are derived from the price the sender paid.
+            // Dividing by the price makes costs comparable across epochs with different gas prices.
+            explanation.gas_price = tx.transaction.as_ref().map(|t| t.data.gas_data().price);
+            let price = explanation.gas_price.filter(|price| *price > 0);
+            explanation.computation_units = price.map(|price| gas_used.computation_cost / price);
+            explanation.gas_units = price.map(|price| explanation.gas_used / price);
         }
 
         if let Some(changes) = &tx.object_changes {
             for change in changes {
-                let obj_change = self.parse_object_change(change);
+                let mut obj_change = self.parse_object_change(change);
+                self.describe_dynamic_field(change, &mut obj_change);
                 explanation.actions.push(obj_change.details.clone());
                 explanation.object_changes.push(obj_change);
             }
         }
 
+        // Unwrapped objects don't get their own object change, so read them from effects
+        if let Some(effects) = &tx.effects {
+            for unwrapped in effects.unwrapped() {
+                explanation.actions.push(format!(
+                    "Unwrapped object {}",
+                    self.shorten_address(&unwrapped.reference.object_id.to_string())
+                ));
+            }
+            for unwrapped in effects.unwrapped_then_deleted() {
+                explanation.actions.push(format!(
+                    "Unwrapped and deleted object {}",
+                    self.shorten_address(&unwrapped.object_id.to_string())
+                ));
+            }
+        }
+
         if let Some(balances) = &tx.balance_changes {
+            // Gas is paid by the gas owner (the sender, unless the transaction is sponsored)
+            let gas_owner = tx.transaction.as_ref().map(|t| t.data.gas_data().owner);
+            let sender = tx.transaction.as_ref().map(|t| *t.data.sender());
+
+            // Net amount per coin type for the sender, keyed by the full type so the order is stable
+            let mut flows: BTreeMap<String, i128> = BTreeMap::new();
+
             for balance in balances {
-                let bal_change = self.parse_balance_change(balance);
-                explanation.balance_changes.push(bal_change);
+                let pays_gas = matches!(balance.owner, Owner::AddressOwner(addr) if Some(addr) == gas_owner)
+                    && balance.coin_type == GAS::type_tag();
+
+                if matches!(balance.owner, Owner::AddressOwner(addr) if Some(addr) == sender) {
+                    // Gas isn't part of the swap, add it back so only the coins that moved remain
+                    let gas = if pays_gas {
+                        explanation.gas_used as i128
+                    } else {
+                        0
+                    };
+                    *flows.entry(balance.coin_type.to_string()).or_default() +=
+                        balance.amount + gas;
+                }
+
+                if pays_gas && explanation.gas_used > 0 {
+                    // Split the gas payment out so it isn't mistaken for SUI being sent
+                    let gas = explanation.gas_used as i128;
+                    let owner = self.balance_owner(&balance.owner);
+                    let coin_type = balance.coin_type.to_string();
+
+                    explanation
+                        .balance_changes
+                        .push(self.build_balance_change(&owner, &coin_type, -gas, true, options));
+
+                    let remainder = balance.amount + gas;
+                    if remainder != 0 {
+                        explanation.balance_changes.push(
+                            self.build_balance_change(
+                                &owner, &coin_type, remainder, false, options,
+                            ),
+                        );
+                    }
+                } else {
+                    let bal_change = self.parse_balance_change(balance, options);
+                    explanation.balance_changes.push(bal_change);
+                }
+            }
+
+            // Keep the sponsor's changes apart from the sender's own
+            if let Some(sponsor) = &explanation.sponsor {
+                for change in &mut explanation.balance_changes {
+                    change.is_sponsor = change.owner == *sponsor;
+                }
+            }
+
+            explanation.coin_flow = flows
+                .into_iter()
+                .filter(|(_, amount)| *amount != 0)
+                .map(|(coin_type, amount)| self.build_coin_flow(&coin_type, amount, options))
+                .collect();
+        }
+
+        // Packages that were published or emitted events, for explorer links
+        let mut packages: Vec<String> = vec![];
+        if let Some(changes) = &tx.object_changes {
+            // An upgrade publishes the new package and mutates the UpgradeCap that authorized it
+            let upgrade_cap_mutated = changes.iter().any(|change| {
+                matches!(change, ObjectChange::Mutated { object_type, .. }
+                    if object_type.to_string().ends_with("::package::UpgradeCap"))
+            });
+
+            for change in changes {
+                if let ObjectChange::Published {
+                    package_id,
+                    version,
+                    ..
+                } = change
+                {
+                    packages.push(package_id.to_string());
+
+                    if let Some(old_package) = upgraded_from.filter(|_| upgrade_cap_mutated) {
+                        explanation.actions.push(format!(
+                            "Upgraded package {} to version {} (new package {})",
+                            self.shorten_address(&old_package.to_string()),
+                            version.value(),
+                            self.shorten_address(&package_id.to_string())
+                        ));
+                        explanation.category = Some("Package Upgrade".to_string());
+                        packages.push(old_package.to_string());
+                    }
+                }
             }
         }
 
+        // Staking and unstaking, told apart by the events the system package emits
+        if let Some((category, action)) = self.staking_action(tx, options) {
+            explanation.actions.push(action);
+            explanation.category = Some(category.to_string());
+        }
+
+        // Plenty of pools change state without emitting a SwapEvent, so swaps are recognized from
+        // what moved instead of what was announced
+        let swap = match explanation.category {
+            None => self.swap_description(&explanation),
+            Some(_) => None,
+        };
+        if let Some(swap) = swap {
+            explanation.actions.push(swap);
+            explanation.category = Some("Swap".to_string());
+        }
+
         if let Some(events) = &tx.events {
             for event in &events.data {
+                let event_type = self.simplify_type(&event.type_.to_string());
                 explanation.events.push(format!(
                     "Event: {} from package {}",
-                    self.simplify_type(&event.type_.to_string()),
-                    event.package_id
+                    event_type, event.package_id
                 ));
+                explanation.event_details.push(EventInfo {
+                    event_type: event_type.clone(),
+                    full_type: event.type_.to_canonical_string(true),
+                    package_id: event.package_id.to_string(),
+                    module: event.transaction_module.to_string(),
+                    sender: event.sender.to_string(),
+                });
+                *explanation.events_by_type.entry(event_type).or_insert(0) += 1;
+                packages.push(event.package_id.to_string());
+                interacted.insert(event.package_id);
+            }
+        }
+        packages.sort();
+        packages.dedup();
+        // Named from ADDRESS_LABELS_PATH, e.g. "Cetus (0x1eab...b2fb)"
+        explanation.packages = interacted
+            .iter()
+            .map(|id| self.shorten_address(&id.to_string()))
+            .collect();
+
+        // Power users can get the effects blob itself next to our summary
+        if options.include_effects {
+            explanation.raw_effects = tx
+                .effects
+                .as_ref()
+                .and_then(|effects| serde_json::to_value(effects).ok());
+        }
+
+        // Developers can ask for the exact types instead of the friendly labels
+        if !options.simplify {
+            for change in &mut explanation.object_changes {
+                change.object_type = change.full_type.clone();
             }
+            for balance in &mut explanation.balance_changes {
+                balance.coin_type = balance.full_type.clone();
+            }
+        }
+
+        // The RPC doesn't promise an order, sort so the same transaction always serializes the same way
+        explanation.object_changes.sort_by(|a, b| {
+            (&a.change_type, &a.object_type, &a.object_id).cmp(&(
+                &b.change_type,
+                &b.object_type,
+                &b.object_id,
+            ))
+        });
+        explanation.balance_changes.sort_by(|a, b| {
+            (&a.owner, &a.coin_type, a.is_gas, a.amount).cmp(&(
+                &b.owner,
+                &b.coin_type,
+                b.is_gas,
+                b.amount,
+            ))
+        });
+
+        explanation.unique_addresses = involved_addresses(&explanation).len();
+        if options.activity {
+            explanation.activity = Some(self.activity_items(&explanation, tx.timestamp_ms));
         }
 
-        explanation.summary = self.generate_summary(&explanation);
+        explanation.summary = self.summarize(&explanation, options);
+        if !explanation.data_available {
+            explanation.summary = format!(
+                "Transaction data pruned, explained from effects only • {}",
+                explanation.summary
+            );
+        }
+        explanation.links = self.config.explorer.links(NETWORK, &explanation, &packages);
 
         Ok(explanation)
     }
 
-    fn parse_object_change(&self, change: &ObjectChange) -> ModelObjectChange {
-        match change {
-            ObjectChange::Created {
-                object_id,
-                object_type,
-                owner,
-                ..
-            } => ModelObjectChange {
-                change_type: "Created".to_string(),
-                object_type: self.simplify_type(&object_type.to_string()),
-                object_id: object_id.to_string(),
-                owner: Some(owner.to_string()),
-                details: format!(
-                    "Created new {} owned by {}",
-                    self.simplify_type(&object_type.to_string()),
-                    self.shorten_address(&owner.to_string())
-                ),
-            },
-            ObjectChange::Transferred {
-                object_id,
-                object_type,
-                sender,
+    //Flatten the sender's coin movements and object changes into feed items, the sender being the actor
+    fn activity_items(
+        &self,
+        explanation: &TransactionExplanation,
+        timestamp: Option<u64>,
+    ) -> Vec<ActivityItem> {
+        let Some(sender) = &explanation.sender else {
+            return vec![];
+        };
+        let item = |verb: &str, object, counterparty, amount| ActivityItem {
+            actor: sender.clone(),
+            verb: verb.to_string(),
+            object,
+            counterparty,
+            amount,
+            timestamp,
+        };
+
+        let mut items = vec![];
+        let coins = explanation
+            .balance_changes
+            .iter()
+            .filter(|change| !change.is_gas && !change.is_sponsor);
+        for change in coins.clone().filter(|change| change.owner == *sender) {
+            let verb = if change.amount < 0 {
+                "sent"
+            } else {
+                "received"
+            };
+
+            // The other side moved the same coin the opposite way
+            let others: Vec<&str> = coins
+                .clone()
+                .filter(|other| other.owner != *sender && other.full_type == change.full_type)
+                .filter(|other| (other.amount < 0) != (change.amount < 0))
+                .map(|other| other.owner.as_str())
+                .collect();
+            let counterparty = match others.as_slice() {
+                [only] => Some(only.to_string()),
+                _ => None,
+            };
+
+            let amount = amount_with_unit(
+                &change.amount_readable,
+                &change.coin_type,
+                &change.full_type,
+            );
+            items.push(item(verb, None, counterparty, Some(amount)));
+        }
+
+        for change in &explanation.object_changes {
+            let verb = match change.change_type.as_str() {
+                "Created" => "created",
+                "Transferred" => "transferred",
+                "Deleted" => "deleted",
+                _ => continue,
+            };
+            let counterparty = match verb {
+                "transferred" => change
+                    .owner
+                    .as_deref()
+                    .and_then(owner_address)
+                    .map(str::to_string),
+                _ => None,
+            };
+            items.push(item(
+                verb,
+                Some(change.object_type.clone()),
+                counterparty,
+                None,
+            ));
+        }
+
+        items
+    }
+
+    //Which watched addresses appear anywhere in the explanation, in watchlist order
+    fn matched_addresses(
+        &self,
+        explanation: &TransactionExplanation,
+        watchlist: &[String],
+    ) -> Vec<String> {
+        let involved = involved_addresses(explanation);
+        watchlist
+            .iter()
+            .filter(|watched| {
+                watched
+                    .parse::<SuiAddress>()
+                    .is_ok_and(|address| involved.contains(&address))
+            })
+            .cloned()
+            .collect()
+    }
+
+    //parse_transaction followed by the enrichers, which is what every explanation goes through
+    async fn parse_and_enrich(
+        &self,
+        digest: &str,
+        tx: &SuiTransactionBlockResponse,
+        options: &ExplainOptions,
+    ) -> Result<TransactionExplanation, ExplainError> {
+        let mut explanation = self.parse_transaction(digest, tx, options)?;
+        self.run_enrichers(&mut explanation, tx).await;
+        Ok(explanation)
+    }
+
+    //One line of history or context. Transactions that can't be explained are left out.
+    async fn context_entry(
+        &self,
+        tx: &SuiTransactionBlockResponse,
+        options: &ExplainOptions,
+    ) -> Option<ContextTransaction> {
+        let digest = tx.digest.to_string();
+        let explanation = self.parse_and_enrich(&digest, tx, options).await.ok()?;
+        Some(ContextTransaction {
+            digest,
+            summary: explanation.summary,
+            timestamp: tx.timestamp_ms,
+            warnings: explanation.warnings,
+        })
+    }
+
+    // In order, each one sees what the ones before it added
+    async fn run_enrichers(
+        &self,
+        explanation: &mut TransactionExplanation,
+        raw: &SuiTransactionBlockResponse,
+    ) {
+        for enricher in &self.enrichers {
+            enricher.enrich(explanation, raw).await;
+        }
+    }
+
+    // Run the enrichments the options ask for. They're independent, so they run side by side and
+    // each one is best effort: a failed lookup leaves its part out instead of failing the rest.
+    async fn enrich(
+        &self,
+        tx_digest: TransactionDigest,
+        explanation: &TransactionExplanation,
+        options: &ExplainOptions,
+    ) -> Enrichments {
+        let sender = explanation
+            .sender
+            .as_deref()
+            .and_then(|s| s.parse::<SuiAddress>().ok());
+
+        let display = async {
+            if options.resolve_display {
+                self.resolve_display(explanation).await
+            } else {
+                BTreeMap::new()
+            }
+        };
+        let sender_balances = async {
+            match (options.include_sender_balance, sender) {
+                (true, Some(sender)) => self.sender_balances(sender, options).await,
+                _ => None,
+            }
+        };
+        let context = async {
+            match (options.include_context, sender) {
+                (true, Some(sender)) => self.context_transactions(tx_digest, sender, options).await,
+                _ => vec![],
+            }
+        };
+        let checkpoint_index = async {
+            match (options.include_checkpoint_position, explanation.checkpoint) {
+                (true, Some(checkpoint)) => self.checkpoint_index(tx_digest, checkpoint).await,
+                _ => None,
+            }
+        };
+
+        let (display, sender_balances, context, checkpoint_index) =
+            futures::join!(display, sender_balances, context, checkpoint_index);
+        Enrichments {
+            display,
+            sender_balances,
+            context,
+            checkpoint_index,
+        }
+    }
+
+    // Ask the endpoint that served the transaction for its newest checkpoint, and how old that is.
+    // A node that stopped following the chain still answers for older transactions, but misses
+    // recent ones, which then look like "not found".
+    async fn check_node_lag(&self, meta: &mut ResponseMeta) {
+        let pool = match meta.source.as_str() {
+            "history" => self.history.as_ref(),
+            _ => Some(self.rpc.as_ref()),
+        };
+        let Some(pool) = pool else {
+            return;
+        };
+        let latest = pool
+            .call_at(&meta.rpc_endpoint, "latest checkpoint", |client| async move {
+                client.read_api().get_checkpoints(None, Some(1), true).await
+            })
+            .await;
+
+        let checkpoint = match latest {
+            // Fixtures and injected fetchers have no endpoint to ask
+            None => return,
+            Some(Ok(page)) => match page.data.into_iter().next() {
+                Some(checkpoint) => checkpoint,
+                None => return,
+            },
+            Some(Err(e)) => {
+                eprintln!(
+                    "{}Failed to fetch latest checkpoint from {}: {}",
+                    request_id::log_prefix(),
+                    meta.rpc_endpoint,
+                    e
+                );
+                return;
+            }
+        };
+
+        let now_ms = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .map_or(0, |now| now.as_millis() as u64);
+        let lag_ms = now_ms.saturating_sub(checkpoint.timestamp_ms);
+        meta.node_latest_checkpoint = Some(checkpoint.sequence_number);
+        meta.node_lag_ms = Some(lag_ms);
+        meta.node_behind = lag_ms > self.config.node_max_lag.as_millis() as u64;
+        if meta.node_behind {
+            eprintln!(
+                "{}RPC endpoint {} is behind, its latest checkpoint {} is {}ms old",
+                request_id::log_prefix(),
+                meta.rpc_endpoint,
+                checkpoint.sequence_number,
+                lag_ms
+            );
+        }
+    }
+
+    // Where the transaction sits in its checkpoint's transaction list
+    async fn checkpoint_index(
+        &self,
+        tx_digest: TransactionDigest,
+        checkpoint: u64,
+    ) -> Option<usize> {
+        let response = self
+            .rpc
+            .call(&format!("Checkpoint {}", checkpoint), |client| async move {
+                client
+                    .read_api()
+                    .get_checkpoint(CheckpointId::SequenceNumber(checkpoint))
+                    .await
+            })
+            .await;
+
+        match response {
+            Ok((checkpoint, _)) => checkpoint.transactions.iter().position(|d| *d == tx_digest),
+            Err(e) => {
+                eprintln!(
+                    "{}Failed to fetch checkpoint {}: {}",
+                    request_id::log_prefix(),
+                    checkpoint,
+                    e
+                );
+                None
+            }
+        }
+    }
+
+    // Fetch Display fields for every created object, DISPLAY_CONCURRENCY at a time.
+    // Types known to have no Display are skipped.
+    async fn resolve_display(
+        &self,
+        explanation: &TransactionExplanation,
+    ) -> BTreeMap<String, BTreeMap<String, String>> {
+        let lookups = explanation
+            .object_changes
+            .iter()
+            .filter(|change| change.change_type == "Created")
+            .filter_map(|change| {
+                let object_id = change.object_id.parse::<ObjectID>().ok()?;
+                let type_key = change.full_type.clone();
+                if self.display_types.lock().unwrap().get(&type_key) == Some(&false) {
+                    return None;
+                }
+                Some((change.object_id.clone(), object_id, type_key))
+            })
+            .map(|(id, object_id, type_key)| async move {
+                let response = self
+                    .rpc
+                    .call(&format!("Object {}", id), |client| async move {
+                        client
+                            .read_api()
+                            .get_object_with_options(
+                                object_id,
+                                SuiObjectDataOptions {
+                                    show_display: true,
+                                    ..Default::default()
+                                },
+                            )
+                            .await
+                    })
+                    .await;
+
+                // Only cache what we actually saw, a failed or missing read says nothing about the type
+                let data = response.ok().and_then(|(r, _)| r.data)?;
+                let fields = data.display.and_then(|d| d.data);
+                self.display_types
+                    .lock()
+                    .unwrap()
+                    .insert(type_key, fields.is_some());
+                fields.map(|fields| (id, fields.into_iter().collect::<BTreeMap<_, _>>()))
+            });
+
+        futures::stream::iter(lookups)
+            .buffer_unordered(DISPLAY_CONCURRENCY)
+            .filter_map(|found| async move { found })
+            .collect()
+            .await
+    }
+
+    // The sender's balance of every coin it holds now. Best effort, a failed lookup leaves it out.
+    async fn sender_balances(
+        &self,
+        sender: SuiAddress,
+        options: &ExplainOptions,
+    ) -> Option<Vec<SenderBalance>> {
+        let (balances, _) = match self
+            .rpc
+            .call(&format!("Balances of {}", sender), |client| async move {
+                client.coin_read_api().get_all_balances(sender).await
+            })
+            .await
+        {
+            Ok(balances) => balances,
+            Err(e) => {
+                eprintln!(
+                    "{}Failed to fetch balances of {}: {}",
+                    request_id::log_prefix(),
+                    sender,
+                    e
+                );
+                return None;
+            }
+        };
+
+        let mut balances: Vec<SenderBalance> = balances
+            .into_iter()
+            .map(|balance| {
+                let coin_type = self.simplify_type(&balance.coin_type);
+                let total = balance.total_balance;
+                let balance_readable = if is_sui(&balance.coin_type) {
+                    format::mist_to_sui_string(total as i128, false, &self.sui_style(options))
+                } else if options.group_digits {
+                    format::group_thousands(&total.to_string())
+                } else {
+                    total.to_string()
+                };
+
+                SenderBalance {
+                    coin_type: if options.simplify {
+                        coin_type
+                    } else {
+                        balance.coin_type.clone()
+                    },
+                    full_type: balance.coin_type,
+                    balance: total,
+                    balance_readable,
+                }
+            })
+            .collect();
+        balances.sort_by(|a, b| a.full_type.cmp(&b.full_type));
+
+        Some(balances)
+    }
+
+    // The sender's transactions right before and after this one, summarized, oldest first.
+    // Best effort like the other enrichments, a failed query just leaves that side out.
+    async fn context_transactions(
+        &self,
+        tx_digest: TransactionDigest,
+        sender: SuiAddress,
+        options: &ExplainOptions,
+    ) -> Vec<ContextTransaction> {
+        let limit = options
+            .context_size
+            .unwrap_or(DEFAULT_CONTEXT_SIZE)
+            .min(MAX_CONTEXT_SIZE);
+        if limit == 0 {
+            return vec![];
+        }
+
+        let mut context = vec![];
+        // Descending from the target gives the ones before it, ascending the ones after
+        for descending in [true, false] {
+            let query = SuiTransactionBlockResponseQuery::new(
+                Some(TransactionFilter::FromAddress(sender)),
+                Some(SuiTransactionBlockResponseOptions {
+                    show_input: true,
+                    show_effects: true,
+                    show_object_changes: true,
+                    show_balance_changes: true,
+                    ..Default::default()
+                }),
+            );
+
+            let page = match self
+                .rpc
+                .call(&format!("Transactions of {}", sender), |client| {
+                    let query = query.clone();
+                    async move {
+                        client
+                            .read_api()
+                            .query_transaction_blocks(
+                                query,
+                                Some(tx_digest),
+                                Some(limit),
+                                descending,
+                            )
+                            .await
+                    }
+                })
+                .await
+            {
+                Ok((page, _)) => page,
+                Err(e) => {
+                    eprintln!(
+                        "{}Failed to fetch transactions around {}: {}",
+                        request_id::log_prefix(),
+                        tx_digest,
+                        e
+                    );
+                    continue;
+                }
+            };
+
+            let mut side = vec![];
+            for neighbour in &page.data {
+                if let Some(entry) = self.context_entry(neighbour, options).await {
+                    side.push(entry);
+                }
+            }
+            if descending {
+                side.reverse();
+            }
+            context.extend(side);
+        }
+
+        context
+    }
+
+    fn parse_object_change(&self, change: &ObjectChange) -> ModelObjectChange {
+        match change {
+            ObjectChange::Created {
+                object_id,
+                object_type,
+                owner,
+                ..
+            } => ModelObjectChange {
+                change_type: "Created".to_string(),
+                object_type: self.simplify_type(&object_type.to_string()),
+                full_type: object_type.to_string(),
+                object_id: object_id.to_string(),
+                owner: Some(format::format_owner(owner)),
+                details: format!(
+                    "Created new {}{}",
+                    self.simplify_type(&object_type.to_string()),
+                    self.owner_clause(owner)
+                ),
+                display: None,
+            },
+            ObjectChange::Transferred {
+                object_id,
+                object_type,
+                sender,
                 recipient,
                 ..
             } => ModelObjectChange {
                 change_type: "Transferred".to_string(),
                 object_type: self.simplify_type(&object_type.to_string()),
+                full_type: object_type.to_string(),
                 object_id: object_id.to_string(),
-                owner: Some(recipient.to_string()),
+                owner: Some(format::format_owner(recipient)),
                 details: format!(
                     "Transferred {} from {} to {}",
                     self.simplify_type(&object_type.to_string()),
                     self.shorten_address(&sender.to_string()),
-                    self.shorten_address(&recipient.to_string())
+                    self.owner_label(recipient)
                 ),
+                display: None,
             },
             ObjectChange::Mutated {
                 object_id,
@@ -158,13 +1762,15 @@ impl SuiClient {
             } => ModelObjectChange {
                 change_type: "Mutated".to_string(),
                 object_type: self.simplify_type(&object_type.to_string()),
+                full_type: object_type.to_string(),
                 object_id: object_id.to_string(),
-                owner: Some(owner.to_string()),
+                owner: Some(format::format_owner(owner)),
                 details: format!(
-                    "Modified {} owned by {}",
+                    "Modified {}{}",
                     self.simplify_type(&object_type.to_string()),
-                    self.shorten_address(&owner.to_string())
+                    self.owner_clause(owner)
                 ),
+                display: None,
             },
             ObjectChange::Deleted {
                 object_id,
@@ -173,43 +1779,297 @@ impl SuiClient {
             } => ModelObjectChange {
                 change_type: "Deleted".to_string(),
                 object_type: self.simplify_type(&object_type.to_string()),
+                full_type: object_type.to_string(),
                 object_id: object_id.to_string(),
                 owner: None,
                 details: format!("Deleted {}", self.simplify_type(&object_type.to_string())),
+                display: None,
+            },
+            ObjectChange::Wrapped {
+                object_id,
+                object_type,
+                ..
+            } => ModelObjectChange {
+                change_type: "Wrapped".to_string(),
+                object_type: self.simplify_type(&object_type.to_string()),
+                full_type: object_type.to_string(),
+                object_id: object_id.to_string(),
+                owner: None,
+                details: format!(
+                    "Wrapped {} {} into parent object",
+                    self.simplify_type(&object_type.to_string()),
+                    self.shorten_address(&object_id.to_string())
+                ),
+                display: None,
             },
             _ => ModelObjectChange {
                 change_type: "Unknown".to_string(),
                 object_type: "Unknown".to_string(),
+                full_type: "Unknown".to_string(),
                 object_id: "Unknown".to_string(),
                 owner: None,
                 details: "Unknown object change".to_string(),
+                display: None,
             },
         }
     }
 
+    //Dynamic fields are 0x2::dynamic_field::Field<K, V> objects owned by their parent, so
+    //"Created Field" is really "added a field to the parent". Reword those changes.
+    fn describe_dynamic_field(&self, change: &ObjectChange, obj_change: &mut ModelObjectChange) {
+        let full_type = self.normalize_type(&obj_change.full_type);
+        let Some(params) = full_type
+            .strip_prefix("0x2::dynamic_field::Field<")
+            .and_then(|rest| rest.strip_suffix('>'))
+        else {
+            return;
+        };
+
+        // Dynamic object fields wrap their key: Field<Wrapper<K>, ID>
+        let key = self
+            .split_type_params(params)
+            .into_iter()
+            .next()
+            .unwrap_or_default();
+        let (key, kind) = match key
+            .strip_prefix("0x2::dynamic_object_field::Wrapper<")
+            .and_then(|k| k.strip_suffix('>'))
+        {
+            Some(inner) => (inner.to_string(), "dynamic object field"),
+            None => (key, "dynamic field"),
+        };
+        let key = self.simplify_type(&key);
+
+        let parent = match change {
+            ObjectChange::Created { owner, .. } | ObjectChange::Mutated { owner, .. } => {
+                match owner {
+                    Owner::ObjectOwner(parent) => Some(self.shorten_address(&parent.to_string())),
+                    _ => None,
+                }
+            }
+            _ => None,
+        };
+
+        obj_change.details = match (obj_change.change_type.as_str(), parent) {
+            ("Created", Some(parent)) => format!("Added {} (key: {}) to {}", kind, key, parent),
+            ("Mutated", Some(parent)) => format!("Updated {} (key: {}) on {}", kind, key, parent),
+            ("Deleted", _) => format!("Removed {} (key: {})", kind, key),
+            _ => return,
+        };
+    }
+
+    // Split "A, B<C, D>" into ["A", "B<C, D>"], only on top-level commas
+    fn split_type_params(&self, params: &str) -> Vec<String> {
+        let mut parts = vec![];
+        let mut depth = 0;
+        let mut current = String::new();
+
+        for c in params.chars() {
+            match c {
+                '<' => depth += 1,
+                '>' => depth -= 1,
+                ',' if depth == 0 => {
+                    parts.push(current.trim().to_string());
+                    current.clear();
+                    continue;
+                }
+                _ => {}
+            }
+            current.push(c);
+        }
+        parts.push(current.trim().to_string());
+
+        parts
+    }
+
     // Convert a SuiBalanceChange into our BalanceChange format
-    fn parse_balance_change(&self, balance: &SuiBalanceChange) -> BalanceChange {
-        let amount = balance.amount;
-        let coin_type = self.simplify_type(&balance.coin_type.to_string());
-
-        // Convert to human-readable format
-        let amount_readable = if coin_type.contains("SUI") {
-            let sui_amount = amount as f64 / 1_000_000_000.0;
-            format!("{:+.6} SUI", sui_amount)
-        } else {
-            format!("{:+}", amount)
+    fn parse_balance_change(
+        &self,
+        balance: &SuiBalanceChange,
+        options: &ExplainOptions,
+    ) -> BalanceChange {
+        self.build_balance_change(
+            &self.balance_owner(&balance.owner),
+            &balance.coin_type.to_string(),
+            balance.amount,
+            false,
+            options,
+        )
+    }
+
+    fn build_balance_change(
+        &self,
+        owner: &str,
+        coin_type: &str,
+        amount: i128,
+        is_gas: bool,
+        options: &ExplainOptions,
+    ) -> BalanceChange {
+        let full_type = coin_type.to_string();
+        let coin_type = self.simplify_type(coin_type);
+        let (amount_readable, decimals_source) = self.readable_amount(&full_type, amount, options);
+        let direction = match amount.signum() {
+            1 => "Received",
+            -1 => "Sent",
+            _ => "None",
         };
 
         BalanceChange {
-            owner: balance.owner.to_string(),
+            owner: owner.to_string(),
             coin_type,
+            full_type,
             amount,
+            direction: direction.to_string(),
             amount_readable,
+            decimals_source: decimals_source.to_string(),
+            is_gas,
+            is_sponsor: false,
+        }
+    }
+
+    fn build_coin_flow(&self, coin_type: &str, amount: i128, options: &ExplainOptions) -> CoinFlow {
+        let label = self.simplify_type(coin_type);
+        let (amount_readable, _) = self.readable_amount(coin_type, amount, options);
+
+        CoinFlow {
+            coin: if options.simplify {
+                label
+            } else {
+                coin_type.to_string()
+            },
+            full_type: coin_type.to_string(),
+            direction: if amount < 0 { "Out" } else { "In" }.to_string(),
+            amount,
+            amount_readable,
+        }
+    }
+
+    //SUI formatting asked for by the request, falling back to SUI_PRECISION for the decimals
+    fn sui_style(&self, options: &ExplainOptions) -> format::SuiStyle {
+        format::SuiStyle {
+            group: options.group_digits,
+            precision: options.sui_precision.unwrap_or(self.config.sui_precision),
+            trim_zeros: options.trim_zeros,
+            mist: options
+                .amount_unit
+                .as_deref()
+                .is_some_and(|unit| unit.eq_ignore_ascii_case("mist")),
+        }
+    }
+
+    //Signed amount of a coin for *_readable fields, and where its decimals came from: SUI is always
+    //known, other coins use their CoinMetadata when load_coin_decimals found it, else stay raw
+    fn readable_amount(
+        &self,
+        full_type: &str,
+        amount: i128,
+        options: &ExplainOptions,
+    ) -> (String, &'static str) {
+        if is_sui(full_type) {
+            return (
+                format::mist_to_sui_string(amount, true, &self.sui_style(options)),
+                "builtin",
+            );
+        }
+        match self.coin_decimals.lock().unwrap().get(full_type) {
+            Some(Some(decimals)) => (
+                format::scaled_amount_string(amount, *decimals, options.group_digits),
+                "metadata",
+            ),
+            _ => (
+                format::raw_amount_string(amount, options.group_digits),
+                "fallback",
+            ),
         }
     }
 
+    //Look up the decimals of every non-SUI coin the transaction moved that we haven't seen yet.
+    //Each lookup gets coin_metadata_timeout, so one obscure token can't hold up the explanation.
+    //Failures and timeouts aren't remembered, those coins fall back to raw amounts this time and
+    //the result is false.
+    async fn load_coin_decimals(&self, tx: &SuiTransactionBlockResponse) -> bool {
+        let coin_types: HashSet<String> = tx
+            .balance_changes
+            .iter()
+            .flatten()
+            .filter(|balance| balance.coin_type != GAS::type_tag())
+            .map(|balance| balance.coin_type.to_string())
+            .filter(|coin_type| !self.coin_decimals.lock().unwrap().contains_key(coin_type))
+            .collect();
+
+        let lookups = coin_types.into_iter().map(|coin_type| async move {
+            let lookup = self
+                .rpc
+                .call(&format!("Metadata of {}", coin_type), |client| {
+                    let coin_type = coin_type.clone();
+                    async move { client.coin_read_api().get_coin_metadata(coin_type).await }
+                });
+            match tokio::time::timeout(self.config.coin_metadata_timeout, lookup).await {
+                Ok(Ok((metadata, _))) => {
+                    self.coin_decimals
+                        .lock()
+                        .unwrap()
+                        .insert(coin_type, metadata.map(|m| m.decimals));
+                    true
+                }
+                Ok(Err(e)) => {
+                    eprintln!(
+                        "{}Failed to fetch coin metadata of {}: {}",
+                        request_id::log_prefix(),
+                        coin_type,
+                        e
+                    );
+                    false
+                }
+                Err(_) => {
+                    eprintln!(
+                        "{}Coin metadata lookup of {} timed out",
+                        request_id::log_prefix(),
+                        coin_type
+                    );
+                    false
+                }
+            }
+        });
+        futures::future::join_all(lookups)
+            .await
+            .into_iter()
+            .all(|found| found)
+    }
+
     //Simplify long type names ("0x2::coin::Coin<0x2::sui::SUI>" -> "SUI Coin")
     fn simplify_type(&self, type_str: &str) -> String {
+        if let Some(label) = self.type_labels.lock().unwrap().get(type_str) {
+            return label.clone();
+        }
+
+        let label = self.simplify_type_uncached(type_str);
+
+        // Types are few but unbounded over the server's life, start over instead of growing forever
+        let mut labels = self.type_labels.lock().unwrap();
+        if labels.len() >= MAX_CACHED_TYPE_LABELS {
+            labels.clear();
+        }
+        labels.insert(type_str.to_string(), label.clone());
+        label
+    }
+
+    fn simplify_type_uncached(&self, type_str: &str) -> String {
+        let type_str = &self.normalize_type(type_str);
+
+        if let Some(coin) = type_str.split_once("::coin::Coin<").map(|(_, coin)| coin) {
+            // Coin<0x...::usdc::USDC> -> "USDC", the struct name is the symbol for nearly every coin.
+            // Only the coin itself decides, an LP coin with SUI among its type arguments isn't SUI.
+            let coin = coin.strip_suffix('>').unwrap_or(coin);
+            if coin == "0x2::sui::SUI" {
+                return "SUI Coin".to_string();
+            }
+            let coin = coin.split('<').next().unwrap_or(coin);
+            return match coin.rsplit("::").next() {
+                Some(symbol) if !symbol.is_empty() => symbol.to_string(),
+                _ => "Coin".to_string(),
+            };
+        }
         if type_str.contains("0x2::sui::SUI") {
             return "SUI Coin".to_string();
         }
@@ -224,12 +2084,353 @@ impl SuiClient {
         type_str.split("::").last().unwrap_or(type_str).to_string()
     }
 
-    //Shorten addresses for readability (0x123...789)
+    //Rewrite every address in a type to its short lowercase form, so "0x0000...0002::sui::SUI" matches "0x2::sui::SUI"
+    fn normalize_type(&self, type_str: &str) -> String {
+        let segments: Vec<&str> = type_str.split("::").collect();
+        let last = segments.len() - 1;
+
+        segments
+            .iter()
+            .enumerate()
+            .map(|(i, segment)| {
+                // Addresses always sit right before a "::", after any generic or list punctuation
+                if i == last {
+                    return segment.to_string();
+                }
+                let start = segment.rfind(['<', ',', ' ']).map_or(0, |p| p + 1);
+                let (head, address) = segment.split_at(start);
+                format!("{}{}", head, self.normalize_address(address))
+            })
+            .collect::<Vec<_>>()
+            .join("::")
+    }
+
+    // "0x00..02" / "0X2" / bare 64-char hex -> "0x2". Anything that isn't an address is returned as is.
+    fn normalize_address(&self, address: &str) -> String {
+        let hex = match address
+            .strip_prefix("0x")
+            .or_else(|| address.strip_prefix("0X"))
+        {
+            Some(hex) => hex,
+            None if address.len() == 64 => address,
+            None => return address.to_string(),
+        };
+
+        if hex.is_empty() || !hex.chars().all(|c| c.is_ascii_hexdigit()) {
+            return address.to_string();
+        }
+
+        let trimmed = hex.trim_start_matches('0').to_lowercase();
+        if trimmed.is_empty() {
+            "0x0".to_string()
+        } else {
+            format!("0x{}", trimmed)
+        }
+    }
+
+    //Name an owner by kind, worded like format_owner with shortened addresses: "0x1234...cdef",
+    //"object 0x1234...cdef", "Shared (initial version 12)" or "Immutable"
+    fn owner_label(&self, owner: &Owner) -> String {
+        format::format_owner_with(owner, |address| self.shorten_address(address))
+    }
+
+    //Who a balance change belongs to, written the same way as object owners
+    fn balance_owner(&self, owner: &Owner) -> String {
+        format::format_owner(owner)
+    }
+
+    // The tail of "Created new X ..." sentences: " owned by 0x12...cdef" or ", Immutable"
+    fn owner_clause(&self, owner: &Owner) -> String {
+        match owner {
+            Owner::AddressOwner(_) | Owner::ObjectOwner(_) => {
+                format!(" owned by {}", self.owner_label(owner))
+            }
+            _ => format!(", {}", self.owner_label(owner)),
+        }
+    }
+
+    //Shorten addresses for readability (0x123...789, see ADDRESS_FORMAT), labeled if known
+    //("DEX Router (0x123...789)", "Clock (0x6)")
     fn shorten_address(&self, address: &str) -> String {
-        if address.len() > 10 {
-            format!("{}...{}", &address[..6], &address[address.len() - 4..])
+        // System objects like the Clock read better by name than as 0x0000...0006
+        if let Some(label) = system_objects::label(address) {
+            return label;
+        }
+
+        let short = self.config.address_format.apply(address);
+
+        match self.address_labels.get(address) {
+            Some(label) => format!("{} ({})", label, short),
+            None => short,
+        }
+    }
+
+    //"Staked 100 SUI with Mysten-1 (0x1234...cdef)" or "Unstaked 100 SUI (+1.2 SUI rewards) from ...",
+    //read from the staking events the system package emits
+    fn staking_action(
+        &self,
+        tx: &SuiTransactionBlockResponse,
+        options: &ExplainOptions,
+    ) -> Option<(&'static str, String)> {
+        let events = tx.events.as_ref()?;
+        let mist = |value: &serde_json::Value| -> Option<i128> {
+            match value {
+                serde_json::Value::String(s) => s.parse().ok(),
+                other => other.as_u64().map(i128::from),
+            }
+        };
+        let sui =
+            |amount: i128| format::mist_to_sui_string(amount, false, &self.sui_style(options));
+
+        events.data.iter().find_map(|event| {
+            let event_type = event.type_.to_string();
+            let fields = &event.parsed_json;
+            let validator = self.validator_label(fields.get("validator_address")?.as_str()?);
+
+            if event_type.ends_with("::validator::StakingRequestEvent") {
+                let amount = mist(fields.get("amount")?)?;
+                Some((
+                    "Stake",
+                    format!("Staked {} with {}", sui(amount), validator),
+                ))
+            } else if event_type.ends_with("::validator::UnstakingRequestEvent") {
+                let principal = mist(fields.get("principal_amount")?)?;
+                let reward = fields.get("reward_amount").and_then(mist).unwrap_or(0);
+                Some((
+                    "Unstake",
+                    format!(
+                        "Unstaked {} (+{} rewards) from {}",
+                        sui(principal),
+                        sui(reward),
+                        validator
+                    ),
+                ))
+            } else {
+                None
+            }
+        })
+    }
+
+    //"Mysten-1 (0x1234...cdef)" when the validator set is loaded and knows the address
+    fn validator_label(&self, address: &str) -> String {
+        let short = self.shorten_address(address);
+        let name = address.parse::<SuiAddress>().ok().and_then(|address| {
+            let validators = self.validators.lock().unwrap();
+            let (_, names) = validators.as_ref()?;
+            names.get(&address.to_string()).cloned()
+        });
+        match name {
+            Some(name) => format!("{} ({})", name, short),
+            None => short,
+        }
+    }
+
+    //Refresh the validator names from the system state unless the copy is fresh. On failure the old
+    //copy (or none) is kept and stakes show validator addresses only.
+    async fn load_validator_names(&self) {
+        let fresh = self
+            .validators
+            .lock()
+            .unwrap()
+            .as_ref()
+            .is_some_and(|(loaded, _)| loaded.elapsed() < VALIDATOR_NAMES_TTL);
+        if fresh {
+            return;
+        }
+
+        let result = self
+            .rpc
+            .call("Validator set", |client| async move {
+                client.governance_api().get_latest_sui_system_state().await
+            })
+            .await;
+        match result {
+            Ok((state, _)) => {
+                let names = state
+                    .active_validators
+                    .into_iter()
+                    .map(|validator| (validator.sui_address.to_string(), validator.name))
+                    .collect();
+                *self.validators.lock().unwrap() = Some((Instant::now(), names));
+            }
+            Err(e) => eprintln!(
+                "{}Could not load the validator set: {}",
+                request_id::log_prefix(),
+                e
+            ),
+        }
+    }
+
+    //"Swapped 5.000000 SUI for 12.3 USDC in Pool" when the sender gave up some coins, got different ones
+    //back and a shared object (the pool) changed along the way. Events aren't needed.
+    fn swap_description(&self, explanation: &TransactionExplanation) -> Option<String> {
+        if explanation.status != "Success" {
+            return None;
+        }
+
+        let pool = explanation.object_changes.iter().find(|change| {
+            change.change_type == "Mutated"
+                && change
+                    .owner
+                    .as_deref()
+                    .is_some_and(|owner| owner.starts_with("Shared"))
+        })?;
+
+        let side = |direction: &str| -> Vec<String> {
+            explanation
+                .coin_flow
+                .iter()
+                .filter(|flow| flow.direction == direction)
+                .map(|flow| amount_with_unit(&flow.amount_readable, &flow.coin, &flow.full_type))
+                .collect()
+        };
+        let (paid, received) = (side("Out"), side("In"));
+        if paid.is_empty() || received.is_empty() {
+            return None;
+        }
+
+        Some(format!(
+            "Swapped {} for {} in {}",
+            paid.join(" + "),
+            received.join(" + "),
+            struct_name(&pool.full_type)
+        ))
+    }
+
+    //"0x1234...cdef sent 5.000000 SUI to 0xabcd...1234" when one coin moved from one address to another
+    fn simple_transfer_summary(&self, explanation: &TransactionExplanation) -> Option<String> {
+        let moved: Vec<&BalanceChange> = explanation
+            .balance_changes
+            .iter()
+            .filter(|balance| !balance.is_gas)
+            .collect();
+
+        let [first, second] = moved.as_slice() else {
+            return None;
+        };
+        let (sent, received) = match (first.amount < 0, second.amount < 0) {
+            (true, false) => (first, second),
+            (false, true) => (second, first),
+            _ => return None,
+        };
+        if sent.full_type != received.full_type || received.amount == 0 {
+            return None;
+        }
+
+        let from = account_address(&sent.owner)?;
+        let to = account_address(&received.owner)?;
+
+        Some(format!(
+            "{} sent {} to {}",
+            self.shorten_address(from),
+            amount_with_unit(
+                &received.amount_readable,
+                &received.coin_type,
+                &received.full_type,
+            ),
+            self.shorten_address(to)
+        ))
+    }
+
+    //Pick the summary length the request asked for, unknown values get the normal one
+    fn summarize(&self, explanation: &TransactionExplanation, options: &ExplainOptions) -> String {
+        match options.verbosity.as_deref() {
+            Some("terse") => self.terse_summary(explanation),
+            Some("detailed") => self.detailed_summary(explanation),
+            _ => self.generate_summary(explanation),
+        }
+    }
+
+    //"Transfer • 5.000000 SUI": what kind of transaction and the main amount it moved
+    fn terse_summary(&self, explanation: &TransactionExplanation) -> String {
+        let category = if explanation.status.starts_with("Failed") {
+            "Failed".to_string()
+        } else if let Some(category) = &explanation.category {
+            category.clone()
+        } else if self.simple_transfer_summary(explanation).is_some() {
+            "Transfer".to_string()
         } else {
-            address.to_string()
+            "Transaction".to_string()
+        };
+
+        // The biggest coin the sender gave up, or what it paid in gas if nothing else left
+        let value = by_magnitude(&explanation.coin_flow)
+            .into_iter()
+            .find(|flow| flow.direction == "Out")
+            .map(|flow| amount_with_unit(&flow.amount_readable, &flow.coin, &flow.full_type))
+            .unwrap_or_else(|| format!("{} gas", gas_text(explanation)));
+
+        format!("{} • {}", category, value)
+    }
+
+    //The normal summary followed by the sender's net movement of every coin, then one sentence per
+    //action and balance change
+    fn detailed_summary(&self, explanation: &TransactionExplanation) -> String {
+        // The normal summary already leads with the failure
+        let mut sentences = vec![self.generate_summary(explanation)];
+        if !explanation.coin_flow.is_empty() {
+            let net: Vec<String> = by_magnitude(&explanation.coin_flow)
+                .into_iter()
+                .map(|flow| {
+                    let sign = if flow.amount < 0 { "-" } else { "+" };
+                    format!(
+                        "{}{}",
+                        sign,
+                        amount_with_unit(&flow.amount_readable, &flow.coin, &flow.full_type)
+                    )
+                })
+                .collect();
+            sentences.push(format!("Net: {}", net.join(", ")));
+        }
+        sentences.extend(explanation.actions.iter().cloned());
+        for balance in explanation.balance_changes.iter().filter(|b| !b.is_gas) {
+            // A coin held by an object isn't an account's balance, say so instead of passing the
+            // parent's ID off as an address
+            let owner = match (
+                account_address(&balance.owner),
+                owner_address(&balance.owner),
+            ) {
+                (Some(address), _) => self.shorten_address(address),
+                (None, Some(parent)) => {
+                    format!("object {}", self.config.address_format.apply(parent))
+                }
+                (None, None) => balance.owner.clone(),
+            };
+            sentences.push(format!(
+                "{} {} {}",
+                owner,
+                if balance.amount < 0 { "lost" } else { "gained" },
+                amount_with_unit(
+                    &balance.amount_readable,
+                    &balance.coin_type,
+                    &balance.full_type,
+                )
+            ));
+        }
+        if explanation.unique_addresses > 0 {
+            sentences.push(format!(
+                "{} address{} involved",
+                explanation.unique_addresses,
+                if explanation.unique_addresses == 1 {
+                    ""
+                } else {
+                    "es"
+                }
+            ));
+        }
+
+        sentences.join(". ")
+    }
+
+    //"Gas: 0.003000 SUI", or who paid it when the transaction was sponsored
+    fn gas_clause(&self, explanation: &TransactionExplanation) -> String {
+        match &explanation.sponsor {
+            Some(sponsor) => format!(
+                "Sponsor {} paid {} gas",
+                self.shorten_address(sponsor),
+                gas_text(explanation)
+            ),
+            None => format!("Gas: {}", gas_text(explanation)),
         }
     }
 
@@ -238,8 +2439,30 @@ impl SuiClient {
         let action_count = explanation.actions.len();
         let balance_count = explanation.balance_changes.len();
 
+        // A failed transaction rolls everything back but gas, so the changes left are just the gas coin
+        if let Some(reason) = failure_text(explanation) {
+            return format!("Failed: {} • {}", reason, self.gas_clause(explanation));
+        }
+
         if action_count == 0 && balance_count == 0 {
-            return format!("Transaction executed with {} gas", explanation.gas_used_sui);
+            return format!("Transaction executed with {} gas", gas_text(explanation));
+        }
+
+        if let Some(transfer) = self.simple_transfer_summary(explanation) {
+            return format!("{} • {}", transfer, self.gas_clause(explanation));
+        }
+
+        let swap = match explanation.category.as_deref() {
+            Some("Swap") => self.swap_description(explanation),
+            Some("Stake") | Some("Unstake") => explanation
+                .actions
+                .iter()
+                .find(|action| action.starts_with("Staked ") || action.starts_with("Unstaked "))
+                .cloned(),
+            _ => None,
+        };
+        if let Some(swap) = swap {
+            return format!("{} • {}", swap, self.gas_clause(explanation));
         }
 
         let mut parts = vec![];
@@ -260,6 +2483,905 @@ impl SuiClient {
             ));
         }
 
-        format!("{} • Gas: {}", parts.join(" • "), explanation.gas_used_sui)
+        format!("{} • {}", parts.join(" • "), self.gas_clause(explanation))
+    }
+}
+
+// Names as auditors know them, MultiSigLegacy is still a multisig
+fn signature_scheme(signature: &GenericSignature) -> &'static str {
+    match signature {
+        GenericSignature::MultiSig(_) | GenericSignature::MultiSigLegacy(_) => "MultiSig",
+        GenericSignature::ZkLoginAuthenticator(_) => "ZkLogin",
+        GenericSignature::PasskeyAuthenticator(_) => "Passkey",
+        GenericSignature::Signature(signature) => match signature {
+            Signature::Ed25519SuiSignature(_) => "ED25519",
+            Signature::Secp256k1SuiSignature(_) => "Secp256k1",
+            Signature::Secp256r1SuiSignature(_) => "Secp256r1",
+        },
+    }
+}
+
+// Whether the transaction emitted staking events, so validator names are worth loading
+fn mentions_staking(tx: &SuiTransactionBlockResponse) -> bool {
+    tx.events.as_ref().is_some_and(|events| {
+        events.data.iter().any(|event| {
+            let event_type = event.type_.to_string();
+            event_type.ends_with("::validator::StakingRequestEvent")
+                || event_type.ends_with("::validator::UnstakingRequestEvent")
+        })
+    })
+}
+
+// "module::function" or "package::module::function", packages compared as addresses
+fn calls_function(call: &MoveCall, function: &str) -> bool {
+    let mut parts = function.rsplitn(3, "::");
+    let (Some(name), Some(module)) = (parts.next(), parts.next()) else {
+        return false;
+    };
+    let package_matches = match parts.next() {
+        Some(package) => match (
+            package.parse::<ObjectID>(),
+            call.package.parse::<ObjectID>(),
+        ) {
+            (Ok(wanted), Ok(called)) => wanted == called,
+            _ => false,
+        },
+        None => true,
+    };
+    package_matches && call.module == module && call.function == name
+}
+
+// Everyone the transaction touched: sender, signers, and owners of changed objects and balances
+//The struct itself, type arguments dropped: "0xabc::pool::Pool<0x2::sui::SUI, 0xdba3::usdc::USDC>" -> "Pool"
+fn struct_name(full_type: &str) -> &str {
+    let base = full_type.split('<').next().unwrap_or(full_type);
+    base.rsplit("::").next().unwrap_or(base)
+}
+
+pub fn involved_addresses(explanation: &TransactionExplanation) -> HashSet<SuiAddress> {
+    let owners = explanation
+        .object_changes
+        .iter()
+        .filter_map(|change| change.owner.as_deref())
+        .chain(explanation.balance_changes.iter().map(|b| b.owner.as_str()))
+        .filter_map(account_address);
+
+    explanation
+        .sender
+        .as_deref()
+        .into_iter()
+        .chain(explanation.signers.iter().map(String::as_str))
+        .chain(owners)
+        .filter_map(|address| address.parse().ok())
+        .collect()
+}
+
+//Why a failed transaction failed: the readable abort reason, else the execution error itself
+//("Failed : Failure { error: \"InsufficientGas\" }" -> "InsufficientGas"). None on success.
+fn failure_text(explanation: &TransactionExplanation) -> Option<String> {
+    let status = explanation.status.strip_prefix("Failed")?;
+    if let Some(reason) = &explanation.failure_reason {
+        return Some(reason.clone());
+    }
+
+    let error = status
+        .split_once("error: \"")
+        .and_then(|(_, rest)| rest.rsplit_once('"'))
+        .map(|(error, _)| error.replace("\\\"", "\""));
+    Some(error.unwrap_or_else(|| status.trim_start_matches([' ', ':']).to_string()))
+}
+
+// "+5.000000 SUI" -> "5.000000 SUI", "-1,500" -> "1,500 USDC". SUI amounts already carry their unit,
+// unless amount_unit kept them in MIST: "+5000000000" -> "5000000000 MIST".
+fn amount_with_unit(amount_readable: &str, coin_label: &str, full_type: &str) -> String {
+    let amount = amount_readable.trim_start_matches(['+', '-']);
+    if !is_sui(full_type) {
+        format!("{} {}", amount, coin_label)
+    } else if amount.ends_with(" SUI") {
+        amount.to_string()
+    } else {
+        format!("{} MIST", amount)
+    }
+}
+
+// SUI itself, decided by type: the label can't tell it from AFSUI or a scam 0xabc::fake::SUI
+fn is_sui(full_type: &str) -> bool {
+    full_type.parse().ok() == Some(GAS::type_tag())
+}
+
+// Biggest movement first. Without prices, amounts of different coins can only be compared raw,
+// ties go by coin so the order is stable.
+fn by_magnitude(flows: &[CoinFlow]) -> Vec<&CoinFlow> {
+    let mut flows: Vec<&CoinFlow> = flows.iter().collect();
+    flows.sort_by(|a, b| {
+        b.amount
+            .unsigned_abs()
+            .cmp(&a.amount.unsigned_abs())
+            .then_with(|| a.coin.cmp(&b.coin))
+    });
+    flows
+}
+
+// Gas for sentences, with its unit whatever amount_unit asked for
+fn gas_text(explanation: &TransactionExplanation) -> String {
+    amount_with_unit(&explanation.gas_used_sui, "SUI", "0x2::sui::SUI")
+}
+
+// The address inside an owner string (see format::format_owner): "0x..." or "object 0x..."
+pub fn owner_address(owner: &str) -> Option<&str> {
+    let start = owner.find("0x")?;
+    let rest = &owner[start..];
+    let end = rest[2..]
+        .find(|c: char| !c.is_ascii_hexdigit())
+        .map_or(rest.len(), |i| i + 2);
+    Some(&rest[..end])
+}
+
+// The account behind an owner string: an address owner or a consensus address owner. Objects
+// holding coins or children aren't accounts, nor are shared and immutable objects.
+pub fn account_address(owner: &str) -> Option<&str> {
+    if owner.starts_with("0x") {
+        owner_address(owner)
+    } else {
+        None
+    }
+}
+
+// What the gas payment costs in MIST after the storage rebate, and the rebate left over when it's
+// larger than the costs (a transaction that mostly deletes objects). One of the two is always 0.
+fn net_gas(gas: &GasCostSummary) -> (u64, u64) {
+    let net = gas.computation_cost as i128 + gas.storage_cost as i128 - gas.storage_rebate as i128;
+    (net.max(0) as u64, (-net).max(0) as u64)
+}
+
+// Object and balance changes bucketed by owner, the sender first and everyone else in address order.
+// Consensus-owned objects land with the rest of their address's changes. Deleted and wrapped
+// objects have no owner any more and only appear in the flat list.
+fn group_by_owner(explanation: &TransactionExplanation) -> Vec<OwnerChanges> {
+    let key = |owner: &str| -> String {
+        if owner.starts_with("0x") {
+            owner_address(owner).unwrap_or(owner).to_string()
+        } else {
+            owner.to_string()
+        }
+    };
+
+    let mut groups: BTreeMap<String, OwnerChanges> = BTreeMap::new();
+    for balance in &explanation.balance_changes {
+        owner_group(&mut groups, key(&balance.owner))
+            .balance_changes
+            .push(balance.clone());
+    }
+    for change in &explanation.object_changes {
+        if let Some(owner) = &change.owner {
+            owner_group(&mut groups, key(owner))
+                .object_changes
+                .push(change.clone());
+        }
+    }
+
+    let mut grouped: Vec<OwnerChanges> = vec![];
+    if let Some(sender) = explanation.sender.as_deref() {
+        grouped.extend(groups.remove(sender));
+    }
+    grouped.extend(groups.into_values());
+    grouped
+}
+
+fn owner_group(groups: &mut BTreeMap<String, OwnerChanges>, owner: String) -> &mut OwnerChanges {
+    groups.entry(owner.clone()).or_insert_with(|| OwnerChanges {
+        owner,
+        object_changes: vec![],
+        balance_changes: vec![],
+    })
+}
+
+// Everything in the options that changes the core explanation, so differently shaped answers don't mix
+fn core_cache_key(digest: &str, options: &ExplainOptions) -> String {
+    format!(
+        "{}|{}|{}|{}|{}|{:?}|{:?}|{}|{}|{:?}",
+        digest,
+        options.simplify,
+        options.status_only,
+        options.include_effects,
+        options.group_digits,
+        options.verbosity,
+        options.sui_precision,
+        options.trim_zeros,
+        options.activity,
+        options.amount_unit
+    )
+}
+
+fn enrichment_cache_key(core_key: &str, options: &ExplainOptions) -> String {
+    format!(
+        "{}|{}|{}|{}|{:?}|{}",
+        core_key,
+        options.resolve_display,
+        options.include_sender_balance,
+        options.include_context,
+        options.context_size,
+        options.include_checkpoint_position
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fixtures::{
+        MockFetcher, address, balance, created_object, digest, effects, response, test_client,
+        test_config, transaction,
+    };
+    use futures::{FutureExt, future::BoxFuture};
+    use sui_types::base_types::SequenceNumber;
+
+    fn client() -> Arc<SuiClient> {
+        test_client(Arc::new(MockFetcher::default()))
+    }
+
+    #[test]
+    fn normalizes_short_long_and_padded_addresses() {
+        let client = client();
+        assert_eq!(client.normalize_address("0x2"), "0x2");
+        assert_eq!(client.normalize_address(&format!("0x{:0>64}", "2")), "0x2");
+        assert_eq!(client.normalize_address(&format!("{:0>64}", "2")), "0x2");
+        assert_eq!(client.normalize_address("0X00AbC"), "0xabc");
+        assert_eq!(client.normalize_address("0x0000"), "0x0");
+        assert_eq!(client.normalize_address("sui"), "sui");
+    }
+
+    #[test]
+    fn normalizes_every_address_in_a_type() {
+        let client = client();
+        let long_sui = format!("0x{:0>64}::sui::SUI", "2");
+        assert_eq!(client.normalize_type(&long_sui), "0x2::sui::SUI");
+        assert_eq!(
+            client.normalize_type(&format!("0x0002::coin::Coin<{}>", long_sui)),
+            "0x2::coin::Coin<0x2::sui::SUI>"
+        );
+        assert_eq!(
+            client.normalize_type("0xABC::pool::Pool<0x02::sui::SUI, 0x00Def::usdc::USDC>"),
+            "0xabc::pool::Pool<0x2::sui::SUI, 0xdef::usdc::USDC>"
+        );
+        assert_eq!(client.simplify_type(&long_sui), "SUI Coin");
+    }
+
+    #[test]
+    fn effects_only_response_is_explained_without_transaction_data() {
+        let digest = digest(3);
+        let tx = response(serde_json::json!({
+            "digest": digest.to_string(),
+            "effects": effects(digest, &address(0xa1), (1_000_000, 2_000_000, 500_000), None),
+        }));
+
+        let explanation = client()
+            .parse_transaction(&digest.to_string(), &tx, &ExplainOptions::default())
+            .unwrap();
+        assert!(!explanation.data_available);
+        assert_eq!(explanation.available_sections, vec!["effects"]);
+        assert_eq!(explanation.sender, None);
+        assert_eq!(explanation.kind, "Unknown");
+        assert_eq!(explanation.status, "Success");
+        assert_eq!(explanation.gas_used, 2_500_000);
+    }
+
+    #[test]
+    fn response_without_transaction_or_effects_is_pruned() {
+        let digest = digest(3);
+        let tx = response(serde_json::json!({ "digest": digest.to_string() }));
+
+        let error = client()
+            .parse_transaction(&digest.to_string(), &tx, &ExplainOptions::default())
+            .unwrap_err();
+        assert_eq!(error.code(), "DATA_PRUNED");
+    }
+
+    #[test]
+    fn shuffled_changes_parse_to_the_same_explanation() {
+        let digest = digest(4);
+        let sender = address(0xa1);
+        let receiver = address(0xb2);
+        let coin = "0x2::coin::Coin<0x2::sui::SUI>";
+        let balances = [
+            balance(&sender, "0x2::sui::SUI", -1_002_500_000),
+            balance(&receiver, "0x2::sui::SUI", 1_000_000_000),
+            balance(&receiver, "0xdba3::usdc::USDC", 7_000),
+        ];
+        let objects = [
+            created_object(&sender, &receiver, coin, &address(0x11)),
+            created_object(&sender, &receiver, coin, &address(0x22)),
+            created_object(&sender, &sender, "0x2::kiosk::Kiosk", &address(0x33)),
+        ];
+        let parse = |order: [usize; 3]| {
+            let tx = response(serde_json::json!({
+                "digest": digest.to_string(),
+                "transaction": transaction(&sender),
+                "effects": effects(digest, &sender, (1_000_000, 2_000_000, 500_000), None),
+                "balanceChanges": order.map(|i| balances[i].clone()),
+                "objectChanges": order.map(|i| objects[i].clone()),
+            }));
+            let explanation = client()
+                .parse_transaction(&digest.to_string(), &tx, &ExplainOptions::default())
+                .unwrap();
+            serde_json::to_string(&explanation).unwrap()
+        };
+
+        let first = parse([0, 1, 2]);
+        assert_eq!(first, parse([2, 0, 1]));
+        assert_eq!(first, parse([1, 2, 0]));
+    }
+
+    fn explain_without_changes(error: Option<&str>) -> TransactionExplanation {
+        let digest = digest(5);
+        let sender = address(0xa1);
+        let tx = response(serde_json::json!({
+            "digest": digest.to_string(),
+            "transaction": transaction(&sender),
+            "effects": effects(digest, &sender, (1_000_000, 2_000_000, 500_000), error),
+            "balanceChanges": [],
+            "objectChanges": [],
+        }));
+        client()
+            .parse_transaction(&digest.to_string(), &tx, &ExplainOptions::default())
+            .unwrap()
+    }
+
+    #[test]
+    fn success_without_changes_reports_only_gas() {
+        let explanation = explain_without_changes(None);
+        assert_eq!(failure_text(&explanation), None);
+        assert_eq!(
+            client().generate_summary(&explanation),
+            "Transaction executed with 0.002500 SUI gas"
+        );
+    }
+
+    #[test]
+    fn failure_without_changes_leads_with_the_error() {
+        let explanation = explain_without_changes(Some("InsufficientGas"));
+        assert_eq!(
+            failure_text(&explanation).as_deref(),
+            Some("InsufficientGas")
+        );
+        assert_eq!(
+            client().generate_summary(&explanation),
+            "Failed: InsufficientGas • Gas: 0.002500 SUI"
+        );
+    }
+
+    #[test]
+    fn failure_text_prefers_the_readable_reason() {
+        let explanation = TransactionExplanation {
+            status: "Failed : Failure { error: \"MoveAbort(..., 1)\" }".to_string(),
+            failure_reason: Some("Insufficient balance".to_string()),
+            ..Default::default()
+        };
+        assert_eq!(
+            failure_text(&explanation).as_deref(),
+            Some("Insufficient balance")
+        );
+
+        let explanation = TransactionExplanation {
+            status: "Failed : unexpected".to_string(),
+            ..Default::default()
+        };
+        assert_eq!(failure_text(&explanation).as_deref(), Some("unexpected"));
+    }
+
+    #[test]
+    fn event_full_type_round_trips_to_the_struct_tag() {
+        let digest = digest(6);
+        let sender = address(0xa1);
+        let package = address(0xc3);
+        let event_type = format!(
+            "{}::pool::SwapEvent<0x2::sui::SUI, {}::usdc::USDC>",
+            package,
+            address(0xd4)
+        );
+        let tx = response(serde_json::json!({
+            "digest": digest.to_string(),
+            "transaction": transaction(&sender),
+            "effects": effects(digest, &sender, (1_000_000, 2_000_000, 500_000), None),
+            "events": [{
+                "id": {"txDigest": digest.to_string(), "eventSeq": "0"},
+                "packageId": package,
+                "transactionModule": "pool",
+                "sender": sender,
+                "type": event_type,
+                "parsedJson": {},
+                "bcsEncoding": "base64",
+                "bcs": "",
+            }],
+        }));
+
+        let explanation = client()
+            .parse_transaction(&digest.to_string(), &tx, &ExplainOptions::default())
+            .unwrap();
+        let emitted = &tx.events.as_ref().unwrap().data[0].type_;
+        let full_type = &explanation.event_details[0].full_type;
+        assert_eq!(full_type.parse().ok().as_ref(), Some(emitted));
+        // Every address is kept whole, including the type arguments
+        assert!(full_type.contains(&package));
+        assert!(full_type.contains(&format!("0x{:064x}::sui::SUI", 2)));
+    }
+
+    const SUI: &str = "0x2::sui::SUI";
+    const USDC: &str = "0xdba3::usdc::USDC";
+
+    fn balance_change(owner: &str, full_type: &str, amount: i128, is_gas: bool) -> BalanceChange {
+        let (coin_type, amount_readable) = if full_type == SUI {
+            (
+                "SUI Coin",
+                format!("{:+.6} SUI", amount as f64 / format::MIST_PER_SUI),
+            )
+        } else {
+            ("USDC", format::raw_amount_string(amount, true))
+        };
+        BalanceChange {
+            owner: owner.to_string(),
+            coin_type: coin_type.to_string(),
+            full_type: full_type.to_string(),
+            amount,
+            direction: if amount < 0 { "Sent" } else { "Received" }.to_string(),
+            amount_readable,
+            decimals_source: "builtin".to_string(),
+            is_gas,
+            is_sponsor: false,
+        }
+    }
+
+    fn with_balances(balance_changes: Vec<BalanceChange>) -> TransactionExplanation {
+        TransactionExplanation {
+            status: "Success".to_string(),
+            sender: Some(address(0xa1)),
+            balance_changes,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn plain_transfer_reads_as_one_sentence() {
+        let explanation = with_balances(vec![
+            balance_change(&address(0xa1), SUI, -5_000_000_000, false),
+            balance_change(&address(0xb2), SUI, 5_000_000_000, false),
+        ]);
+        assert_eq!(
+            client().simple_transfer_summary(&explanation).as_deref(),
+            Some("0xa1a1...a1a1 sent 5.000000 SUI to 0xb2b2...b2b2")
+        );
+    }
+
+    #[test]
+    fn multi_coin_transaction_is_not_a_simple_transfer() {
+        let explanation = with_balances(vec![
+            balance_change(&address(0xa1), SUI, -5_000_000_000, false),
+            balance_change(&address(0xb2), SUI, 5_000_000_000, false),
+            balance_change(&address(0xb2), USDC, -1_000_000, false),
+            balance_change(&address(0xa1), USDC, 1_000_000, false),
+        ]);
+        assert_eq!(client().simple_transfer_summary(&explanation), None);
+
+        let explanation = with_balances(vec![
+            balance_change(&address(0xa1), SUI, -5_000_000_000, false),
+            balance_change(&address(0xb2), USDC, 1_000_000, false),
+        ]);
+        assert_eq!(client().simple_transfer_summary(&explanation), None);
+    }
+
+    #[test]
+    fn gas_only_transaction_is_not_a_transfer() {
+        let explanation =
+            with_balances(vec![balance_change(&address(0xa1), SUI, -2_500_000, true)]);
+        assert_eq!(client().simple_transfer_summary(&explanation), None);
+    }
+
+    #[test]
+    fn simplifies_coin_types_to_their_symbol() {
+        let client = client();
+        let usdc = "0xdba34672e30cb065b1f93e3ab55318768fd6fef66c15942c9f7cb846e2f900e7::usdc::USDC";
+        let cetus =
+            "0x06864a6f921804860930db6ddbe2e16acdf8504495ea7481637a1c8b9a8fe54b::cetus::CETUS";
+        let afsui =
+            "0xf325ce1300e8dac124071d3152c5c5ee6174914f8bc2161e88329cf579246efc::afsui::AFSUI";
+
+        assert_eq!(client.simplify_type(usdc), "USDC");
+        assert_eq!(
+            client.simplify_type(&format!("0x2::coin::Coin<{}>", cetus)),
+            "CETUS"
+        );
+        assert_eq!(
+            client.simplify_type(&format!("0x2::coin::Coin<{}>", afsui)),
+            "AFSUI"
+        );
+        assert_eq!(client.simplify_type("0x2::sui::SUI"), "SUI Coin");
+        assert_eq!(
+            client.simplify_type(&format!("0x{:064x}::coin::Coin<0x2::sui::SUI>", 2)),
+            "SUI Coin"
+        );
+    }
+
+    #[test]
+    fn simplifies_coins_with_generic_arguments_by_the_coin_itself() {
+        let client = client();
+        assert_eq!(
+            client
+                .simplify_type("0x2::coin::Coin<0xabc::lp::LP<0x2::sui::SUI, 0xdba3::usdc::USDC>>"),
+            "LP"
+        );
+        assert_eq!(
+            client.simplify_type(
+                "0x2::coin::Coin<0xabc::vault::Share<0xdef::pool::Pool<0x2::sui::SUI>>>"
+            ),
+            "Share"
+        );
+    }
+
+    fn flow(coin: &str, full_type: &str, amount: i128, amount_readable: &str) -> CoinFlow {
+        CoinFlow {
+            coin: coin.to_string(),
+            full_type: full_type.to_string(),
+            direction: if amount < 0 { "Out" } else { "In" }.to_string(),
+            amount,
+            amount_readable: amount_readable.to_string(),
+        }
+    }
+
+    fn three_coin_swap() -> TransactionExplanation {
+        TransactionExplanation {
+            status: "Success".to_string(),
+            gas_used_sui: "0.002500 SUI".to_string(),
+            coin_flow: vec![
+                flow("USDC", USDC, 500_000_000, "+500"),
+                flow("SUI Coin", SUI, -10_000_000_000, "-10.000000 SUI"),
+                flow("CETUS", "0x6864::cetus::CETUS", 2_000_000_000, "+2"),
+            ],
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn swaps_name_the_pool_without_its_type_arguments() {
+        let pool_type = format!("0xabc::pool::Pool<{}, {}>", SUI, USDC);
+        let swap = TransactionExplanation {
+            coin_flow: vec![
+                flow("SUI Coin", SUI, -10_000_000_000, "-10.000000 SUI"),
+                flow("USDC", USDC, 500_000_000, "+500"),
+            ],
+            object_changes: vec![ObjectMod {
+                change_type: "Mutated".to_string(),
+                object_type: client().simplify_type(&pool_type),
+                full_type: pool_type,
+                object_id: address(0x11),
+                owner: Some("Shared (initial version 3)".to_string()),
+                details: String::new(),
+                display: None,
+            }],
+            ..three_coin_swap()
+        };
+
+        assert_eq!(
+            client().swap_description(&swap).as_deref(),
+            Some("Swapped 10.000000 SUI for 500 USDC in Pool")
+        );
+    }
+
+    #[test]
+    fn orders_coin_flows_by_raw_magnitude() {
+        let swap = three_coin_swap();
+        let coins: Vec<&str> = by_magnitude(&swap.coin_flow)
+            .iter()
+            .map(|flow| flow.coin.as_str())
+            .collect();
+        assert_eq!(coins, ["SUI Coin", "CETUS", "USDC"]);
+
+        // Equal amounts go by coin, whichever way they moved
+        let tied = [
+            flow("USDT", "0xc060::coin::USDT", 5, "+5"),
+            flow("USDC", USDC, -5, "-5"),
+        ];
+        let coins: Vec<&str> = by_magnitude(&tied)
+            .iter()
+            .map(|flow| flow.coin.as_str())
+            .collect();
+        assert_eq!(coins, ["USDC", "USDT"]);
+    }
+
+    #[test]
+    fn detailed_summary_nets_every_coin_of_a_swap() {
+        let summary = client().detailed_summary(&three_coin_swap());
+        assert!(
+            summary.contains("Net: -10.000000 SUI, +2 CETUS, +500 USDC"),
+            "{}",
+            summary
+        );
+    }
+
+    #[test]
+    fn terse_summary_keeps_the_dominant_coin() {
+        assert_eq!(
+            client().terse_summary(&three_coin_swap()),
+            "Transaction • 10.000000 SUI"
+        );
+    }
+
+    #[test]
+    fn balance_owners_are_written_like_object_owners() {
+        let client = client();
+        let account: SuiAddress = address(0xa1).parse().unwrap();
+        let parent: SuiAddress = address(0xc3).parse().unwrap();
+
+        assert_eq!(
+            client.balance_owner(&Owner::AddressOwner(account)),
+            address(0xa1)
+        );
+        assert_eq!(
+            client.balance_owner(&Owner::ObjectOwner(parent)),
+            format!("object {}", address(0xc3))
+        );
+        assert_eq!(
+            client.balance_owner(&Owner::ConsensusAddressOwner {
+                start_version: SequenceNumber::from_u64(5),
+                owner: account,
+            }),
+            format!("{} (consensus, since version 5)", address(0xa1))
+        );
+    }
+
+    #[test]
+    fn owner_labels_read_like_owner_fields() {
+        let client = client();
+        let account: SuiAddress = address(0xa1).parse().unwrap();
+        let full = account.to_string();
+        let short = client.shorten_address(&full);
+        let version = SequenceNumber::from_u64(12);
+
+        for owner in [
+            Owner::AddressOwner(account),
+            Owner::ObjectOwner(account),
+            Owner::Shared {
+                initial_shared_version: version,
+            },
+            Owner::Immutable,
+            Owner::ConsensusAddressOwner {
+                start_version: version,
+                owner: account,
+            },
+        ] {
+            assert_eq!(
+                client.owner_label(&owner),
+                format::format_owner(&owner).replace(&full, &short)
+            );
+        }
+    }
+
+    #[test]
+    fn object_owners_are_not_accounts() {
+        let object = format!("object {}", address(0xc3));
+        let consensus = format!("{} (consensus, since version 5)", address(0xa1));
+        assert_eq!(
+            account_address(&address(0xa1)),
+            Some(address(0xa1).as_str())
+        );
+        assert_eq!(account_address(&consensus), Some(address(0xa1).as_str()));
+        assert_eq!(account_address(&object), None);
+        assert_eq!(account_address("Shared (initial version 12)"), None);
+        assert_eq!(owner_address(&object), Some(address(0xc3).as_str()));
+    }
+
+    #[test]
+    fn coins_held_by_objects_stay_objects_in_summaries() {
+        let object = format!("object {}", address(0xc3));
+        let explanation = with_balances(vec![
+            balance_change(&address(0xa1), SUI, -5_000_000_000, false),
+            balance_change(&object, SUI, 5_000_000_000, false),
+        ]);
+
+        assert_eq!(client().simple_transfer_summary(&explanation), None);
+        let involved = involved_addresses(&explanation);
+        assert!(involved.contains(&address(0xa1).parse().unwrap()));
+        assert!(!involved.contains(&address(0xc3).parse().unwrap()));
+
+        let summary = client().detailed_summary(&explanation);
+        assert!(
+            summary.contains("object 0xc3c3...c3c3 gained 5.000000 SUI"),
+            "{}",
+            summary
+        );
+    }
+
+    #[test]
+    fn unique_addresses_counts_accounts_only() {
+        let digest = digest(7);
+        let sender = address(0xa1);
+        let mut dynamic_field =
+            created_object(&sender, &sender, "0x2::kiosk::Kiosk", &address(0x44));
+        dynamic_field["owner"] = serde_json::json!({"ObjectOwner": address(0xc3)});
+        let tx = response(serde_json::json!({
+            "digest": digest.to_string(),
+            "transaction": transaction(&sender),
+            "effects": effects(digest, &sender, (1_000_000, 2_000_000, 500_000), None),
+            "balanceChanges": [
+                balance(&sender, "0x2::sui::SUI", -1_002_500_000),
+                balance(&address(0xb2), "0x2::sui::SUI", 1_000_000_000),
+            ],
+            "objectChanges": [
+                created_object(&sender, &address(0xb2), "0x2::kiosk::Kiosk", &address(0x33)),
+                dynamic_field,
+            ],
+        }));
+
+        let explanation = client()
+            .parse_transaction(&digest.to_string(), &tx, &ExplainOptions::default())
+            .unwrap();
+        // The sender and 0xb2, not the object the dynamic field hangs off
+        assert_eq!(explanation.unique_addresses, 2);
+    }
+
+    #[test]
+    fn only_sui_itself_gets_sui_formatting() {
+        let client = client();
+        let options = ExplainOptions::default();
+        let afsui =
+            "0xf325ce1300e8dac124071d3152c5c5ee6174914f8bc2161e88329cf579246efc::afsui::AFSUI";
+        let fake = "0xabc::fake::SUI";
+
+        assert!(is_sui(SUI));
+        assert!(is_sui(&format!("0x{:064x}::sui::SUI", 2)));
+        assert!(!is_sui(afsui));
+        assert!(!is_sui(fake));
+
+        assert_eq!(
+            client.readable_amount(SUI, 1_500_000_000, &options),
+            ("+1.500000 SUI".to_string(), "builtin")
+        );
+        assert_eq!(
+            client.readable_amount(fake, 1_500_000_000, &options),
+            ("+1,500,000,000".to_string(), "fallback")
+        );
+        assert_eq!(
+            amount_with_unit("+1,500,000,000", "SUI", fake),
+            "1,500,000,000 SUI"
+        );
+        assert_eq!(amount_with_unit("-1,500", "AFSUI", afsui), "1,500 AFSUI");
+        assert_eq!(
+            amount_with_unit("+5000000000", "SUI Coin", SUI),
+            "5000000000 MIST"
+        );
+        assert_eq!(
+            amount_with_unit("+5.000000 SUI", "SUI Coin", SUI),
+            "5.000000 SUI"
+        );
+    }
+
+    #[tokio::test]
+    async fn only_checkpointed_explanations_are_cached() {
+        let sender = address(0xa1);
+        let pending = digest(10);
+        let finalized = digest(11);
+        let fetcher = MockFetcher::default()
+            .with_response(response(serde_json::json!({
+                "digest": pending.to_string(),
+                "transaction": transaction(&sender),
+                "effects": effects(pending, &sender, (1_000_000, 2_000_000, 500_000), None),
+            })))
+            .with_response(response(serde_json::json!({
+                "digest": finalized.to_string(),
+                "transaction": transaction(&sender),
+                "effects": effects(finalized, &sender, (1_000_000, 2_000_000, 500_000), None),
+                "checkpoint": "42",
+            })));
+        let client = test_client(Arc::new(fetcher));
+        let options = ExplainOptions::default();
+
+        for digest in [pending, finalized] {
+            client
+                .explain_transaction(&digest.to_string(), &options)
+                .await
+                .unwrap();
+        }
+        let (_, meta) = client
+            .explain_transaction(&pending.to_string(), &options)
+            .await
+            .unwrap();
+        assert!(!meta.cached);
+        let (explanation, meta) = client
+            .explain_transaction(&finalized.to_string(), &options)
+            .await
+            .unwrap();
+        assert!(meta.cached);
+        assert_eq!(explanation.checkpoint, Some(42));
+    }
+
+    #[tokio::test]
+    async fn type_filter_leaves_matches_and_grouping_whole() {
+        let digest = digest(12);
+        let sender = address(0xa1);
+        let receiver = address(0xb2);
+        let fetcher = MockFetcher::default().with_response(response(serde_json::json!({
+            "digest": digest.to_string(),
+            "transaction": transaction(&sender),
+            "effects": effects(digest, &sender, (1_000_000, 2_000_000, 500_000), None),
+            "objectChanges": [
+                created_object(&sender, &receiver, "0x2::kiosk::Kiosk", &address(0x11)),
+                created_object(&sender, &sender, "0xabc::nft::Nft", &address(0x22)),
+            ],
+        })));
+        let options = ExplainOptions {
+            watchlist: vec![receiver.clone()],
+            object_type_filter: vec!["nft".to_string()],
+            group_by_owner: true,
+            ..ExplainOptions::default()
+        };
+
+        let (explanation, _) = test_client(Arc::new(fetcher))
+            .explain_transaction(&digest.to_string(), &options)
+            .await
+            .unwrap();
+        assert_eq!(explanation.object_changes.len(), 1);
+        // The receiver's kiosk was filtered out, but the receiver was still involved
+        assert_eq!(explanation.matched_addresses, vec![receiver.clone()]);
+        let grouped: usize = explanation
+            .by_owner
+            .iter()
+            .map(|group| group.object_changes.len())
+            .sum();
+        assert_eq!(grouped, 2);
+    }
+
+    #[tokio::test]
+    async fn history_limit_of_zero_lists_nothing() {
+        let history = client()
+            .address_history(&address(0xa1), Some(0), None, None)
+            .await
+            .unwrap();
+        assert!(history.is_empty());
+    }
+
+    // Flags every explanation, to see where enrichers run
+    struct Tagger;
+
+    impl ExplanationEnricher for Tagger {
+        fn enrich<'a>(
+            &'a self,
+            explanation: &'a mut TransactionExplanation,
+            _raw: &'a SuiTransactionBlockResponse,
+        ) -> BoxFuture<'a, ()> {
+            explanation.warnings.push("Tagged".to_string());
+            async {}.boxed()
+        }
+    }
+
+    #[tokio::test]
+    async fn added_enrichers_run_after_the_built_in_ones() {
+        let digest = digest(8);
+        let sender = address(0xa1);
+        let fetcher = MockFetcher::default().with_response(response(serde_json::json!({
+            "digest": digest.to_string(),
+            "transaction": transaction(&sender),
+            "effects": effects(digest, &sender, (1_000_000, 2_000_000, 500_000), None),
+        })));
+        let config = Arc::new(test_config());
+        let rpc = Arc::new(RpcPool::from_config(&config));
+        let client = SuiClient::with_fetcher(config, rpc, Arc::new(fetcher)).with_enricher(Tagger);
+
+        let (explanation, _) = client
+            .explain_transaction(&digest.to_string(), &ExplainOptions::default())
+            .await
+            .unwrap();
+        assert_eq!(
+            explanation.warnings.last().map(String::as_str),
+            Some("Tagged")
+        );
+
+        // History and context entries go through the same enrichers
+        let tx = response(serde_json::json!({
+            "digest": digest.to_string(),
+            "effects": effects(digest, &sender, (1_000_000, 2_000_000, 500_000), None),
+        }));
+        let entry = client
+            .context_entry(&tx, &ExplainOptions::default())
+            .await
+            .unwrap();
+        assert_eq!(entry.warnings.last().map(String::as_str), Some("Tagged"));
     }
 }
@@ -0,0 +1,20 @@
+use std::str::FromStr;
+use sui_types::base_types::ObjectID;
+
+// System objects that live at the same fixed ID on every network, (ID, name). Add new ones here.
+const WELL_KNOWN: &[(&str, &str)] = &[
+    ("0x5", "Sui System State"),
+    ("0x6", "Clock"),
+    ("0x7", "Authenticator State"),
+    ("0x8", "Random"),
+    ("0x403", "Deny List"),
+];
+
+//"Clock (0x6)" for a well-known system object however its ID is spelled (0x6, 0x00..06), None otherwise
+pub fn label(id: &str) -> Option<String> {
+    let id = ObjectID::from_str(id).ok()?;
+    WELL_KNOWN
+        .iter()
+        .find(|(known, _)| ObjectID::from_str(known).is_ok_and(|known| known == id))
+        .map(|(short, name)| format!("{} ({})", name, short))
+}